@@ -0,0 +1,64 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nine_patch_drawable::{
+    LayoutCache, NinePatchBuilder, NinePatchDrawable, NinePatchLayout, ScaleOptions,
+};
+
+fn drawable() -> NinePatchDrawable {
+    NinePatchBuilder::new(48, 48)
+        .h_stretch(8.0..12.0)
+        .h_stretch(20.0..28.0)
+        .h_stretch(36.0..40.0)
+        .v_stretch(8.0..12.0)
+        .v_stretch(20.0..28.0)
+        .v_stretch(36.0..40.0)
+        .build()
+        .unwrap()
+}
+
+fn scale(c: &mut Criterion) {
+    let drawable = drawable();
+    let options = ScaleOptions::default();
+
+    c.bench_function("scale_to", |b| {
+        b.iter(|| black_box(drawable.scale_to(black_box(320), black_box(240))))
+    });
+    c.bench_function("patches", |b| {
+        b.iter(|| {
+            drawable
+                .patches(black_box(320), black_box(240))
+                .map(|patch| patch.target.right)
+                .sum::<f32>()
+        })
+    });
+    let mut patches = vec![];
+    c.bench_function("scale_into", |b| {
+        b.iter(|| {
+            drawable
+                .scale_into(black_box(320), black_box(240), &options, &mut patches)
+                .unwrap();
+            black_box(&patches);
+        })
+    });
+}
+
+fn layout(c: &mut Criterion) {
+    let drawable = drawable();
+    let options = ScaleOptions::default();
+
+    c.bench_function("layout", |b| {
+        b.iter(|| NinePatchLayout::new(&drawable, black_box(320), black_box(240), &options))
+    });
+    let mut cache = LayoutCache::new(drawable.clone(), options, 8);
+    c.bench_function("layout_cache_hit", |b| {
+        b.iter(|| {
+            let layout = cache.get(black_box(320), black_box(240)).unwrap();
+            layout
+                .patches()
+                .map(|patch| patch.target.right)
+                .sum::<f32>()
+        })
+    });
+}
+
+criterion_group!(benches, scale, layout);
+criterion_main!(benches);
@@ -0,0 +1,365 @@
+//! Inspect, render, validate and detect nine patch bitmaps.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::process::ExitCode;
+
+use nine_patch_drawable::{
+    clear_transparent_border, detect_slices, lint, mark_up, render, Axis, NinePatchDrawable,
+    NinePatchError, PatchKind, RenderOptions, Section,
+};
+
+const USAGE: &str = "\
+usage: ninepatch inspect <file.9.png>
+       ninepatch render <file.9.png> --size <width>x<height> -o <out.png>
+       ninepatch validate <file.9.png>...
+       ninepatch detect <file.png> [-o <out.9.png>]";
+
+/// Problems with the command line or the file system, as opposed to problems
+/// with the nine patch itself.
+const EXIT_USAGE: u8 = 2;
+
+/// A decoded RGBA bitmap.
+struct Bitmap {
+    pixels: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+enum Error {
+    Usage(String),
+    Io(String),
+    NinePatch(String, NinePatchError),
+    /// Invalid files were already reported.
+    Invalid,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("inspect") => inspect(&args[1..]),
+        Some("render") => render_command(&args[1..]),
+        Some("validate") => validate(&args[1..]),
+        Some("detect") => detect(&args[1..]),
+        _ => Err(Error::Usage("missing command".into())),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Error::Usage(message)) => {
+            eprintln!("ninepatch: {message}\n{USAGE}");
+            ExitCode::from(EXIT_USAGE)
+        }
+        Err(Error::Io(message)) => {
+            eprintln!("ninepatch: {message}");
+            ExitCode::from(EXIT_USAGE)
+        }
+        Err(Error::NinePatch(path, error)) => {
+            eprintln!("{path}: {error}");
+            ExitCode::FAILURE
+        }
+        Err(Error::Invalid) => ExitCode::FAILURE,
+    }
+}
+
+fn inspect(args: &[String]) -> Result<(), Error> {
+    let [path] = args else {
+        return Err(Error::Usage("inspect takes one file".into()));
+    };
+    let (bitmap, drawable) = load(path)?;
+    println!("{path}: {}x{}", bitmap.width, bitmap.height);
+    print_sections(Axis::Horizontal, &drawable.h_sections);
+    print_sections(Axis::Vertical, &drawable.v_sections);
+    println!(
+        "margins: left {}, top {}, right {}, bottom {}",
+        drawable.margin_left, drawable.margin_top, drawable.margin_right, drawable.margin_bottom
+    );
+    let optical = &drawable.optical_insets;
+    println!(
+        "optical insets: left {}, top {}, right {}, bottom {}",
+        optical.left, optical.top, optical.right, optical.bottom
+    );
+    println!("patches:");
+    let patches = drawable.scale_to(drawable.width, drawable.height);
+    for row in patches.chunks(drawable.h_sections.len().max(1)) {
+        let cells: Vec<&str> = row
+            .iter()
+            .map(|p| match (p.h_kind, p.v_kind) {
+                (PatchKind::Fixed, PatchKind::Fixed) => "fixed",
+                (_, PatchKind::Fixed) => "h-stretch",
+                (PatchKind::Fixed, _) => "v-stretch",
+                _ => "stretch",
+            })
+            .collect();
+        println!("  {}", cells.join(" "));
+    }
+    for warning in warnings(&drawable) {
+        println!("warning: {warning}");
+    }
+    let lints = lint(
+        &bitmap.pixels,
+        bitmap.width * 4,
+        bitmap.width,
+        bitmap.height,
+    )
+    .map_err(|e| Error::NinePatch(path.clone(), e))?;
+    for warning in lints {
+        println!("warning: {warning}");
+    }
+    Ok(())
+}
+
+fn print_sections(axis: Axis, sections: &[Section]) {
+    println!("{axis} sections:");
+    for s in sections {
+        let kind = match s.kind {
+            PatchKind::Unknown => "unknown",
+            PatchKind::Fixed => "fixed",
+            PatchKind::Stretching => "stretching",
+            PatchKind::Tiling => "tiling",
+        };
+        println!("  {}..{} {kind}", s.start, s.start + s.len);
+    }
+}
+
+/// Things that are allowed but probably not intended.
+fn warnings(drawable: &NinePatchDrawable) -> Vec<String> {
+    let mut warnings = vec![];
+    for (axis, sections) in [
+        (Axis::Horizontal, &drawable.h_sections),
+        (Axis::Vertical, &drawable.v_sections),
+    ] {
+        if sections.iter().all(|s| s.kind == PatchKind::Fixed) {
+            let error = NinePatchError::NoStretchSection { axis };
+            warnings.push(format!("{error}, the whole axis will stretch"));
+        }
+    }
+    if let Err(error) = drawable.validate() {
+        warnings.push(error.to_string());
+    }
+    warnings
+}
+
+fn render_command(args: &[String]) -> Result<(), Error> {
+    let mut input = None;
+    let mut output = None;
+    let mut size = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => size = args.next().map(|s| parse_size(s)).transpose()?,
+            "-o" => output = args.next(),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(Error::Usage(format!("unexpected argument {arg}"))),
+        }
+    }
+    let (Some(input), Some(output), Some((width, height))) = (input, output, size) else {
+        return Err(Error::Usage("render needs a file, --size and -o".into()));
+    };
+    let (bitmap, drawable) = load(input)?;
+    let pixels = render(
+        &bitmap.pixels,
+        bitmap.width * 4,
+        &drawable,
+        width,
+        height,
+        &RenderOptions::default(),
+    )
+    .map_err(|e| Error::NinePatch(input.clone(), e))?;
+    save(output, &pixels, width, height)
+}
+
+fn validate(args: &[String]) -> Result<(), Error> {
+    if args.is_empty() {
+        return Err(Error::Usage("validate takes at least one file".into()));
+    }
+    let mut valid = true;
+    for path in args {
+        match load(path)
+            .and_then(|(_, d)| d.validate().map_err(|e| Error::NinePatch(path.clone(), e)))
+        {
+            Ok(()) => println!("{path}: ok"),
+            Err(Error::NinePatch(path, error)) => {
+                println!("{path}: {error}");
+                valid = false;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Invalid)
+    }
+}
+
+fn detect(args: &[String]) -> Result<(), Error> {
+    let mut input = None;
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => output = args.next(),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(Error::Usage(format!("unexpected argument {arg}"))),
+        }
+    }
+    let Some(input) = input else {
+        return Err(Error::Usage("detect takes one file".into()));
+    };
+    let bitmap = decode(input).map_err(|e| Error::Io(format!("{input}: {e}")))?;
+    let stride = bitmap.width * 4;
+    let detection = detect_slices(&bitmap.pixels, stride, bitmap.width, bitmap.height)
+        .map_err(|e| Error::NinePatch(input.clone(), e))?;
+    let drawable = &detection.drawable;
+    println!("{input}: {}x{}", bitmap.width, bitmap.height);
+    print_sections(Axis::Horizontal, &drawable.h_sections);
+    print_sections(Axis::Vertical, &drawable.v_sections);
+    println!("confidence: {:.2}", detection.confidence);
+    if let Some(output) = output {
+        let pixels = mark_up(
+            &bitmap.pixels,
+            stride,
+            bitmap.width,
+            bitmap.height,
+            drawable,
+        )
+        .map_err(|e| Error::NinePatch(input.clone(), e))?;
+        save(output, &pixels, drawable.width, drawable.height)?;
+    }
+    Ok(())
+}
+
+fn parse_size(size: &str) -> Result<(usize, usize), Error> {
+    size.split_once('x')
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+        .ok_or_else(|| Error::Usage(format!("invalid size {size}")))
+}
+
+fn load(path: &str) -> Result<(Bitmap, NinePatchDrawable), Error> {
+    let mut bitmap = decode(path).map_err(|e| Error::Io(format!("{path}: {e}")))?;
+    let stride = bitmap.width * 4;
+    clear_transparent_border(&mut bitmap.pixels, stride, bitmap.width, bitmap.height)
+        .map_err(|e| Error::NinePatch(path.to_string(), e))?;
+    let drawable = NinePatchDrawable::new(
+        &bitmap.pixels,
+        bitmap.width * 4,
+        bitmap.width,
+        bitmap.height,
+    )
+    .map_err(|e| Error::NinePatch(path.to_string(), e))?;
+    Ok((bitmap, drawable))
+}
+
+fn decode(path: &str) -> Result<Bitmap, png::DecodingError> {
+    let mut decoder = png::Decoder::new(File::open(path)?);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    let (width, height) = (info.width as usize, info.height as usize);
+    let samples = info.color_type.samples();
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for row in buf.chunks(info.line_size).take(height) {
+        for p in row[..width * samples].chunks(samples) {
+            match *p {
+                [g] => pixels.extend_from_slice(&[g, g, g, 0xFF]),
+                [g, a] => pixels.extend_from_slice(&[g, g, g, a]),
+                [r, g, b] => pixels.extend_from_slice(&[r, g, b, 0xFF]),
+                [r, g, b, a] => pixels.extend_from_slice(&[r, g, b, a]),
+                _ => unreachable!(),
+            }
+        }
+    }
+    Ok(Bitmap {
+        pixels,
+        width,
+        height,
+    })
+}
+
+fn save(path: &str, pixels: &[u8], width: usize, height: usize) -> Result<(), Error> {
+    let io_error = |e: &dyn std::fmt::Display| Error::Io(format!("{path}: {e}"));
+    let file = File::create(path).map_err(|e| io_error(&e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| io_error(&e))?;
+    writer.write_image_data(pixels).map_err(|e| io_error(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transparent_black_border() {
+        // A 10 x 10 .9.png as Android tools write it: a transparent black
+        // border with opaque black markers.
+        let mut pixels = vec![];
+        for y in 0..10 {
+            for x in 0..10 {
+                let pixel = match (x, y) {
+                    (1..=8, 1..=8) => [0xC0, 0x20, 0x20, 0xFF],
+                    (3..=6, 0) | (0, 3..=6) | (2..=7, 9) | (9, 2..=7) => [0, 0, 0, 0xFF],
+                    _ => [0, 0, 0, 0],
+                };
+                pixels.extend_from_slice(&pixel);
+            }
+        }
+        let path = std::env::temp_dir().join("ninepatch-transparent-black-border.9.png");
+        let path = path.to_str().unwrap();
+        assert!(save(path, &pixels, 10, 10).is_ok());
+        let Ok((_, drawable)) = load(path) else {
+            panic!("{path} did not load");
+        };
+        std::fs::remove_file(path).unwrap();
+
+        let kinds = |sections: &[Section]| {
+            sections
+                .iter()
+                .map(|s| (s.start, s.len, s.kind))
+                .collect::<Vec<_>>()
+        };
+        let expected = [
+            (0.0, 2.0, PatchKind::Fixed),
+            (2.0, 4.0, PatchKind::Stretching),
+            (6.0, 2.0, PatchKind::Fixed),
+        ];
+        assert_eq!(kinds(&drawable.h_sections), expected);
+        assert_eq!(kinds(&drawable.v_sections), expected);
+        assert_eq!((drawable.margin_left, drawable.margin_right), (1.0, 1.0));
+        assert_eq!(drawable.validate(), Ok(()));
+    }
+
+    #[test]
+    fn no_stretch_markers() {
+        // A 6 x 6 .9.png with padding markers only.
+        let mut pixels = vec![];
+        for y in 0..6 {
+            for x in 0..6 {
+                let pixel = match (x, y) {
+                    (1..=4, 1..=4) => [0xC0, 0x20, 0x20, 0xFF],
+                    (2..=3, 5) | (5, 2..=3) => [0, 0, 0, 0xFF],
+                    _ => [0, 0, 0, 0],
+                };
+                pixels.extend_from_slice(&pixel);
+            }
+        }
+        let path = std::env::temp_dir().join("ninepatch-no-stretch-markers.9.png");
+        let path = path.to_str().unwrap();
+        assert!(save(path, &pixels, 6, 6).is_ok());
+        let Ok((_, drawable)) = load(path) else {
+            panic!("{path} did not load");
+        };
+        std::fs::remove_file(path).unwrap();
+
+        let warnings = warnings(&drawable);
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            let error = NinePatchError::NoStretchSection { axis };
+            assert!(
+                warnings.contains(&format!("{error}, the whole axis will stretch")),
+                "{warnings:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+use crate::{Axis, Insets, NinePatchDrawable, NinePatchError, PatchKind, Section};
+
+/// Builds a [`NinePatchDrawable`] from explicit slices instead of a marked up
+/// bitmap.
+///
+/// The size passed to [`NinePatchBuilder::new`] is the size of the plain
+/// image. The resulting drawable follows the same conventions as one parsed
+/// from a nine patch bitmap: its `width` and `height` include the one pixel
+/// marker border, and the source rectangles returned by
+/// [`NinePatchDrawable::scale_to`] are offset by that border.
+#[derive(Debug, Clone)]
+pub struct NinePatchBuilder {
+    width: usize,
+    height: usize,
+    h_stretch: Vec<Range<f32>>,
+    v_stretch: Vec<Range<f32>>,
+    padding: Insets,
+    optical_insets: Insets,
+}
+
+impl NinePatchBuilder {
+    /// Start building a drawable for a `width` x `height` image.
+    pub fn new(width: usize, height: usize) -> NinePatchBuilder {
+        NinePatchBuilder {
+            width,
+            height,
+            h_stretch: vec![],
+            v_stretch: vec![],
+            padding: Insets::default(),
+            optical_insets: Insets::default(),
+        }
+    }
+
+    /// Slice the image into a three by three grid. The insets are the sizes of
+    /// the fixed borders, the remaining center stretches.
+    ///
+    /// This replaces any previously added stretch ranges.
+    pub fn insets(mut self, insets: Insets) -> NinePatchBuilder {
+        self.h_stretch = vec![insets.left..self.width as f32 - insets.right];
+        self.v_stretch = vec![insets.top..self.height as f32 - insets.bottom];
+        self
+    }
+
+    /// Add a horizontally stretching range of columns.
+    pub fn h_stretch(mut self, range: Range<f32>) -> NinePatchBuilder {
+        self.h_stretch.push(range);
+        self
+    }
+
+    /// Add a vertically stretching range of rows.
+    pub fn v_stretch(mut self, range: Range<f32>) -> NinePatchBuilder {
+        self.v_stretch.push(range);
+        self
+    }
+
+    /// Set the content padding, the equivalent of the right and bottom
+    /// markers of a nine patch bitmap.
+    pub fn padding(mut self, padding: Insets) -> NinePatchBuilder {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the optical bounds insets, the equivalent of the red layout bounds
+    /// markers of a nine patch bitmap.
+    pub fn optical_insets(mut self, optical_insets: Insets) -> NinePatchBuilder {
+        self.optical_insets = optical_insets;
+        self
+    }
+
+    pub fn build(self) -> Result<NinePatchDrawable, NinePatchError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(NinePatchError::InvalidBitmap);
+        }
+        let mut drawable = NinePatchDrawable::from_sections(
+            self.width + 2,
+            self.height + 2,
+            sections(Axis::Horizontal, &self.h_stretch, self.width as f32)?,
+            sections(Axis::Vertical, &self.v_stretch, self.height as f32)?,
+            self.padding,
+        )?;
+        drawable.optical_insets = self.optical_insets;
+        Ok(drawable)
+    }
+}
+
+/// Fill the gaps between the stretching ranges with fixed sections.
+fn sections(axis: Axis, ranges: &[Range<f32>], len: f32) -> Result<Vec<Section>, NinePatchError> {
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_by(|&a, &b| ranges[a].start.total_cmp(&ranges[b].start));
+
+    let mut cursor = 0.0;
+    let mut sections = vec![];
+    for index in order {
+        let range = &ranges[index];
+        if range.start < 0.0 || range.end > len {
+            return Err(NinePatchError::SectionOutOfBounds { axis, index });
+        }
+        if range.start >= range.end {
+            return Err(NinePatchError::EmptySection { axis, index });
+        }
+        if range.start < cursor {
+            return Err(NinePatchError::SectionOverlap { axis, index });
+        }
+        if range.start > cursor {
+            sections.push(Section {
+                start: cursor,
+                len: range.start - cursor,
+                kind: PatchKind::Fixed,
+            });
+        }
+        sections.push(Section {
+            start: range.start,
+            len: range.end - range.start,
+            kind: PatchKind::Stretching,
+        });
+        cursor = range.end;
+    }
+    if cursor < len {
+        sections.push(Section {
+            start: cursor,
+            len: len - cursor,
+            kind: PatchKind::Fixed,
+        });
+    }
+    Ok(sections)
+}
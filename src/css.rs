@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+use crate::{NinePatchDrawable, NinePatchError, PatchKind, Section};
+
+/// Describe the drawable as CSS `border-image` declarations.
+///
+/// `source_url` must point to the image without the nine patch marker
+/// border. It is escaped as a CSS string, including `<` so the CSS can be
+/// embedded in HTML. Only drawables with a single stretching section per axis can be
+/// represented, others fail with [`NinePatchError::UnsupportedSections`].
+///
+/// CSS places padding inside the border, so the padding is the distance
+/// between each margin and the corresponding slice. Margins smaller than the
+/// slice cannot be represented and become a padding of zero.
+pub fn to_css(drawable: &NinePatchDrawable, source_url: &str) -> Result<String, NinePatchError> {
+    let slice = drawable.slice_insets()?;
+    let padding = |margin: f32, slice: f32| (margin - slice).max(0.0);
+    let mut css = String::new();
+    writeln!(css, "border-style: solid;").unwrap();
+    writeln!(
+        css,
+        "border-width: {}px {}px {}px {}px;",
+        slice.top, slice.right, slice.bottom, slice.left
+    )
+    .unwrap();
+    writeln!(css, "border-image-source: url(\"{}\");", escape(source_url)).unwrap();
+    writeln!(
+        css,
+        "border-image-slice: {} {} {} {} fill;",
+        slice.top, slice.right, slice.bottom, slice.left
+    )
+    .unwrap();
+    writeln!(
+        css,
+        "border-image-width: {}px {}px {}px {}px;",
+        slice.top, slice.right, slice.bottom, slice.left
+    )
+    .unwrap();
+    writeln!(
+        css,
+        "border-image-repeat: {} {};",
+        repeat(&drawable.h_sections),
+        repeat(&drawable.v_sections)
+    )
+    .unwrap();
+    writeln!(
+        css,
+        "padding: {}px {}px {}px {}px;",
+        padding(drawable.margin_top, slice.top),
+        padding(drawable.margin_right, slice.right),
+        padding(drawable.margin_bottom, slice.bottom),
+        padding(drawable.margin_left, slice.left)
+    )
+    .unwrap();
+    Ok(css)
+}
+
+/// A standalone HTML page showing the drawable at each of `sizes`, for
+/// design review.
+pub fn html_preview(
+    drawable: &NinePatchDrawable,
+    source_url: &str,
+    sizes: &[(usize, usize)],
+) -> Result<String, NinePatchError> {
+    let css = to_css(drawable, source_url)?;
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Nine patch preview</title>\n<style>\n");
+    html.push_str("body { font-family: sans-serif; background: #eee; }\n");
+    html.push_str(".nine-patch {\n  box-sizing: border-box;\n  display: inline-block;\n");
+    html.push_str("  margin: 8px;\n  vertical-align: top;\n");
+    for line in css.lines() {
+        writeln!(html, "  {line}").unwrap();
+    }
+    html.push_str("}\n.content { outline: 1px dashed #00f; height: 100%; }\n");
+    html.push_str("</style>\n</head>\n<body>\n");
+    for (width, height) in sizes {
+        writeln!(
+            html,
+            "<div class=\"nine-patch\" style=\"width: {width}px; height: {height}px\">\
+             <div class=\"content\">{width}&times;{height}</div></div>"
+        )
+        .unwrap();
+    }
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+fn repeat(sections: &[Section]) -> &'static str {
+    if sections.iter().any(|s| s.kind == PatchKind::Tiling) {
+        "repeat"
+    } else {
+        "stretch"
+    }
+}
+
+/// Escape `value` for a double quoted CSS string.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() || matches!(c, '<' | '>' | '&') => {
+                write!(escaped, "\\{:x} ", c as u32).unwrap()
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
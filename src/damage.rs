@@ -0,0 +1,92 @@
+use crate::{Patch, RectF};
+
+/// The areas to repaint when a drawable drawn as `old` at `old_origin` is
+/// drawn as `new` at `new_origin` instead.
+///
+/// The patch lists are usually the results of
+/// [`crate::NinePatchDrawable::scale_to`] for the old and the new size of a
+/// widget, the origins are where their target coordinates are placed. Only
+/// patches that moved, changed size or changed source are damaged, both where
+/// they were and where they are now, so fixed corners that stay in place are
+/// skipped. Patch lists of different lengths come from different drawables
+/// and are damaged completely.
+///
+/// Damaged rectangles are merged when their union covers no more than the
+/// rectangles themselves, the result never includes pixels that did not
+/// change.
+pub fn damaged_rects(
+    old: &[Patch],
+    old_origin: (f32, f32),
+    new: &[Patch],
+    new_origin: (f32, f32),
+) -> Vec<RectF> {
+    let mut damage = vec![];
+    let moved = |patch: &Patch, (x, y): (f32, f32)| RectF {
+        left: patch.target.left + x,
+        top: patch.target.top + y,
+        right: patch.target.right + x,
+        bottom: patch.target.bottom + y,
+    };
+    if old.len() == new.len() {
+        for (a, b) in old.iter().zip(new) {
+            let (old_target, new_target) = (moved(a, old_origin), moved(b, new_origin));
+            if old_target != new_target
+                || a.source != b.source
+                || (a.h_kind, a.v_kind) != (b.h_kind, b.v_kind)
+            {
+                damage.push(old_target);
+                damage.push(new_target);
+            }
+        }
+    } else {
+        damage.extend(old.iter().map(|patch| moved(patch, old_origin)));
+        damage.extend(new.iter().map(|patch| moved(patch, new_origin)));
+    }
+    damage.retain(|rect| area(rect) > 0.0);
+    merge(damage)
+}
+
+/// Repeatedly replace two rectangles by their union where it adds no area.
+fn merge(mut rects: Vec<RectF>) -> Vec<RectF> {
+    let mut i = 0;
+    while i < rects.len() {
+        let merged = (i + 1..rects.len()).find_map(|j| {
+            let union = union(&rects[i], &rects[j]);
+            let covered =
+                area(&rects[i]) + area(&rects[j]) - intersection_area(&rects[i], &rects[j]);
+            (area(&union) <= covered).then_some((j, union))
+        });
+        match merged {
+            Some((j, union)) => {
+                rects.swap_remove(j);
+                rects[i] = union;
+                // The grown rectangle may now merge with earlier ones.
+                i = 0;
+            }
+            None => i += 1,
+        }
+    }
+    rects
+}
+
+fn area(rect: &RectF) -> f32 {
+    (rect.right - rect.left).max(0.0) * (rect.bottom - rect.top).max(0.0)
+}
+
+fn union(a: &RectF, b: &RectF) -> RectF {
+    RectF {
+        left: a.left.min(b.left),
+        top: a.top.min(b.top),
+        right: a.right.max(b.right),
+        bottom: a.bottom.max(b.bottom),
+    }
+}
+
+fn intersection_area(a: &RectF, b: &RectF) -> f32 {
+    area(&RectF {
+        left: a.left.max(b.left),
+        top: a.top.max(b.top),
+        right: a.right.min(b.right),
+        bottom: a.bottom.min(b.bottom),
+    })
+}
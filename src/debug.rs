@@ -0,0 +1,148 @@
+use crate::transform::flip_horizontally;
+use crate::{
+    render, NinePatchDrawable, NinePatchError, PatchKind, PixelFormat, RectF, RenderOptions,
+};
+
+/// Overlay for patches stretching in one direction.
+const STRETCH_ONE_AXIS: [u8; 4] = [0x00, 0xC0, 0x00, 0x60];
+/// Overlay for patches stretching in both directions.
+const STRETCH_BOTH_AXES: [u8; 4] = [0xFF, 0x00, 0xFF, 0x60];
+const CONTENT_BOX: [u8; 4] = [0x00, 0x00, 0xFF, 0xFF];
+const OPTICAL_BOUNDS: [u8; 4] = [0xFF, 0x00, 0x00, 0xFF];
+const DASH: [[u8; 4]; 2] = [[0x00, 0x00, 0x00, 0xFF], [0xFF, 0xFF, 0xFF, 0xFF]];
+
+/// Like [`render`], with overlays that show how the drawable is laid out:
+///
+/// - stretching patches are tinted green when they stretch along one axis and
+///   magenta when they stretch along both, fixed patches are left as is,
+/// - section boundaries are drawn as black and white dashed lines,
+/// - the content box given by the margins is outlined in blue,
+/// - the optical bounds are outlined in red.
+///
+/// The overlay colors follow [`RenderOptions::format`].
+pub fn render_debug(
+    bitmap: &[u8],
+    stride: usize,
+    drawable: &NinePatchDrawable,
+    width: usize,
+    height: usize,
+    options: &RenderOptions,
+) -> Result<Vec<u8>, NinePatchError> {
+    let ltr = RenderOptions {
+        rtl: false,
+        ..options.clone()
+    };
+    let mut pixels = render(bitmap, stride, drawable, width, height, &ltr)?;
+    let patches = drawable.scale_to_with(width + 2, height + 2, &options.scale)?;
+    let mut canvas = Canvas {
+        pixels: &mut pixels,
+        width,
+        height,
+        format: options.format,
+    };
+
+    for patch in &patches {
+        let overlay = match (patch.h_kind, patch.v_kind) {
+            (PatchKind::Fixed, PatchKind::Fixed) => continue,
+            (PatchKind::Fixed, _) | (_, PatchKind::Fixed) => STRETCH_ONE_AXIS,
+            _ => STRETCH_BOTH_AXES,
+        };
+        let (x_range, y_range) = crate::render::pixel_range(&patch.target, width, height);
+        for y in y_range {
+            for x in x_range.clone() {
+                canvas.blend(x, y, overlay);
+            }
+        }
+    }
+
+    for patch in &patches {
+        for x in [patch.target.left, patch.target.right] {
+            let x = canvas.column(x);
+            for y in 0..height {
+                canvas.blend(x, y, DASH[y / 2 % 2]);
+            }
+        }
+        for y in [patch.target.top, patch.target.bottom] {
+            let y = canvas.row(y);
+            for x in 0..width {
+                canvas.blend(x, y, DASH[x / 2 % 2]);
+            }
+        }
+    }
+
+    canvas.outline(
+        &drawable.content_rect(width + 2, height + 2, &options.scale),
+        CONTENT_BOX,
+    );
+    let density = options.scale.density;
+    let optical = &drawable.optical_insets;
+    canvas.outline(
+        &RectF {
+            left: 1.0 + optical.left * density,
+            top: 1.0 + optical.top * density,
+            right: width as f32 + 1.0 - optical.right * density,
+            bottom: height as f32 + 1.0 - optical.bottom * density,
+        },
+        OPTICAL_BOUNDS,
+    );
+    if options.rtl {
+        flip_horizontally(&mut pixels, width);
+    }
+    Ok(pixels)
+}
+
+struct Canvas<'a> {
+    pixels: &'a mut [u8],
+    width: usize,
+    height: usize,
+    format: PixelFormat,
+}
+
+impl Canvas<'_> {
+    /// The output column of the line at `x`, in marker border coordinates.
+    fn column(&self, x: f32) -> usize {
+        ((x - 1.0).floor().max(0.0) as usize).min(self.width.saturating_sub(1))
+    }
+
+    /// The output row of the line at `y`, in marker border coordinates.
+    fn row(&self, y: f32) -> usize {
+        ((y - 1.0).floor().max(0.0) as usize).min(self.height.saturating_sub(1))
+    }
+
+    fn outline(&mut self, rect: &RectF, color: [u8; 4]) {
+        if self.width == 0 || self.height == 0 || rect.left >= rect.right || rect.top >= rect.bottom
+        {
+            return;
+        }
+        let (left, right) = (self.column(rect.left), self.column(rect.right - 1.0));
+        let (top, bottom) = (self.row(rect.top), self.row(rect.bottom - 1.0));
+        for x in left..=right {
+            self.blend(x, top, color);
+            self.blend(x, bottom, color);
+        }
+        for y in top..=bottom {
+            self.blend(left, y, color);
+            self.blend(right, y, color);
+        }
+    }
+
+    /// Draw `color` over the pixel at `x`, `y`.
+    fn blend(&mut self, x: usize, y: usize, color: [u8; 4]) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let color = self.format.swap_rgba(color);
+        let p = (y * self.width + x) * 4;
+        let dst = &mut self.pixels[p..p + 4];
+        let sa = color[3] as f32 / 255.0;
+        let da = dst[3] as f32 / 255.0;
+        let a = sa + da * (1.0 - sa);
+        if a > 0.0 {
+            for c in 0..3 {
+                let value = (color[c] as f32 * sa + dst[c] as f32 * da * (1.0 - sa)) / a;
+                dst[c] = value.round() as u8;
+            }
+        }
+        dst[3] = (a * 255.0).round() as u8;
+    }
+}
@@ -0,0 +1,162 @@
+use std::ops::Range;
+
+use crate::{Insets, NinePatchBuilder, NinePatchDrawable, NinePatchError, PatchKind, Section};
+
+/// A nine patch proposed by [`detect_slices`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct SliceDetection {
+    pub drawable: NinePatchDrawable,
+    /// From `0.0` to `1.0`, the smaller share of the width or the height
+    /// taken by its stretching band. `0.0` if an axis has no band, the whole
+    /// axis then stretches and the image distorts.
+    pub confidence: f32,
+}
+
+/// Propose the slices of a plain RGBA image without marker border.
+///
+/// Each axis stretches the longest band of consecutive columns, or rows,
+/// whose pixels are all equal, so stretching it looks exactly like the image
+/// drawn larger. The content padding is the area of the bands, at least one
+/// pixel from each edge since a padding marker cannot be empty.
+///
+/// Use [`mark_up`] to turn the result into a nine patch bitmap for review.
+pub fn detect_slices(
+    bitmap: &[u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+) -> Result<SliceDetection, NinePatchError> {
+    if bitmap.len() != stride * height || stride < width * 4 || width < 3 || height < 3 {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    let pixel = |x: usize, y: usize| &bitmap[y * stride + x * 4..y * stride + x * 4 + 4];
+    let columns = uniform_band(width, |x| {
+        (0..height).all(|y| pixel(x, y) == pixel(x - 1, y))
+    });
+    let rows = uniform_band(height, |y| {
+        (0..width).all(|x| pixel(x, y) == pixel(x, y - 1))
+    });
+
+    let mut builder = NinePatchBuilder::new(width, height);
+    if let Some(band) = &columns {
+        builder = builder.h_stretch(band.start as f32..band.end as f32);
+    }
+    if let Some(band) = &rows {
+        builder = builder.v_stretch(band.start as f32..band.end as f32);
+    }
+    let (left, right) = padding(columns.as_ref(), width);
+    let (top, bottom) = padding(rows.as_ref(), height);
+    let drawable = builder
+        .padding(Insets {
+            left,
+            top,
+            right,
+            bottom,
+        })
+        .build()?;
+    let share =
+        |band: Option<&Range<usize>>, len: usize| band.map_or(0.0, |b| b.len() as f32 / len as f32);
+    Ok(SliceDetection {
+        drawable,
+        confidence: share(columns.as_ref(), width).min(share(rows.as_ref(), height)),
+    })
+}
+
+/// Add a marker border for `drawable` around a plain RGBA image, the inverse
+/// of [`NinePatchDrawable::new`].
+///
+/// The result is `width + 2` by `height + 2` pixels with a stride of
+/// `(width + 2) * 4`, with opaque black markers on a transparent background
+/// like the `.9.png` files of Android. Fails with
+/// [`NinePatchError::InvalidMargin`] if a margin is zero, which the padding
+/// markers cannot express.
+pub fn mark_up(
+    bitmap: &[u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+    drawable: &NinePatchDrawable,
+) -> Result<Vec<u8>, NinePatchError> {
+    if bitmap.len() != stride * height
+        || stride < width * 4
+        || drawable.width != width + 2
+        || drawable.height != height + 2
+    {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    let margins = [
+        (drawable.margin_left, drawable.margin_right, width),
+        (drawable.margin_top, drawable.margin_bottom, height),
+    ];
+    for (start, end, len) in margins {
+        if start < 1.0 || end < 1.0 || start + end >= len as f32 {
+            return Err(NinePatchError::InvalidMargin);
+        }
+    }
+
+    let out_stride = (width + 2) * 4;
+    let mut out = vec![0; out_stride * (height + 2)];
+    for y in 0..height {
+        let o = (y + 1) * out_stride + 4;
+        out[o..o + width * 4].copy_from_slice(&bitmap[y * stride..y * stride + width * 4]);
+    }
+    let mut mark = |x: usize, y: usize, marker: bool| {
+        let o = y * out_stride + x * 4;
+        let color = if marker {
+            [0x00, 0x00, 0x00, 0xFF]
+        } else {
+            [0xFF, 0xFF, 0xFF, 0x00]
+        };
+        out[o..o + 4].copy_from_slice(&color);
+    };
+    let stretching = |sections: &[Section], i: usize| {
+        sections.iter().any(|s| {
+            s.kind != PatchKind::Fixed && (s.start..s.start + s.len).contains(&(i as f32 + 0.5))
+        })
+    };
+    let content = |start: f32, end: f32, len: usize, i: usize| {
+        (start..len as f32 - end).contains(&(i as f32 + 0.5))
+    };
+    for x in 0..width {
+        mark(x + 1, 0, stretching(&drawable.h_sections, x));
+        mark(
+            x + 1,
+            height + 1,
+            content(drawable.margin_left, drawable.margin_right, width, x),
+        );
+    }
+    for y in 0..height {
+        mark(0, y + 1, stretching(&drawable.v_sections, y));
+        mark(
+            width + 1,
+            y + 1,
+            content(drawable.margin_top, drawable.margin_bottom, height, y),
+        );
+    }
+    Ok(out)
+}
+
+/// The longest run of lines where `same(i)` tells that line `i` equals line
+/// `i - 1`, if at least two lines long.
+fn uniform_band(len: usize, same: impl Fn(usize) -> bool) -> Option<Range<usize>> {
+    let mut best: Option<Range<usize>> = None;
+    let mut start = 0;
+    for i in 1..=len {
+        if i == len || !same(i) {
+            if i - start >= 2 && best.as_ref().is_none_or(|b| i - start > b.len()) {
+                best = Some(start..i);
+            }
+            start = i;
+        }
+    }
+    best
+}
+
+fn padding(band: Option<&Range<usize>>, len: usize) -> (f32, f32) {
+    let (start, end) = band.map_or((1, 1), |b| (b.start.max(1), (len - b.end).max(1)));
+    if start + end >= len {
+        (1.0, 1.0)
+    } else {
+        (start as f32, end as f32)
+    }
+}
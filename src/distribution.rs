@@ -0,0 +1,88 @@
+use std::fmt::Debug;
+
+use crate::{Axis, PatchKind, Section};
+
+/// Decides how the stretching sections of an axis share the growth beyond
+/// their source length, set with [`crate::ScaleOptions::distribution`].
+///
+/// Each stretching section keeps its source length and grows by a share of
+/// the remaining length proportional to its weight, so a section with a
+/// weight of zero keeps its source length. The weights are asked for once
+/// per axis when scaling.
+/// Negative and non-finite weights count as zero, and if all weights of an
+/// axis are zero the sections grow proportionally to their source lengths.
+/// Targets smaller than the source shrink the stretching sections
+/// proportionally whatever the policy, and axes without stretching sections
+/// follow [`crate::StretchFallback`] instead.
+pub trait StretchPolicy: Debug + Send + Sync {
+    /// The weight of the stretching section `sections[index]` of `axis`.
+    fn weight(&self, axis: Axis, sections: &[Section], index: usize) -> f32;
+}
+
+/// Grow proportionally to the source lengths, like Android. This is the
+/// default.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Proportional;
+
+impl StretchPolicy for Proportional {
+    fn weight(&self, _axis: Axis, sections: &[Section], index: usize) -> f32 {
+        sections[index].len
+    }
+}
+
+/// Grow every stretching section by the same length, whatever the source
+/// lengths.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct EqualShare;
+
+impl StretchPolicy for EqualShare {
+    fn weight(&self, _axis: Axis, _sections: &[Section], _index: usize) -> f32 {
+        1.0
+    }
+}
+
+/// Only grow the middle stretching section, or the two middle ones when
+/// there is an even number of them. The other stretching sections keep their
+/// source length.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct CenterOnly;
+
+impl StretchPolicy for CenterOnly {
+    fn weight(&self, _axis: Axis, sections: &[Section], index: usize) -> f32 {
+        let count = stretching(sections).count();
+        let ordinal = stretching(sections).take_while(|&i| i < index).count();
+        if count.abs_diff(ordinal * 2 + 1) <= 1 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Explicit weights per stretching section, in the order of the sections.
+/// Missing weights count as zero.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct StretchWeights {
+    pub horizontal: Vec<f32>,
+    pub vertical: Vec<f32>,
+}
+
+impl StretchPolicy for StretchWeights {
+    fn weight(&self, axis: Axis, sections: &[Section], index: usize) -> f32 {
+        let weights = match axis {
+            Axis::Horizontal => &self.horizontal,
+            Axis::Vertical => &self.vertical,
+        };
+        let ordinal = stretching(sections).take_while(|&i| i < index).count();
+        weights.get(ordinal).copied().unwrap_or(0.0)
+    }
+}
+
+/// The indices of the stretching sections.
+fn stretching(sections: &[Section]) -> impl Iterator<Item = usize> + '_ {
+    sections
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.kind != PatchKind::Fixed)
+        .map(|(i, _)| i)
+}
@@ -0,0 +1,261 @@
+//! Slice metadata of game engines.
+
+use crate::{Insets, NinePatchBuilder, NinePatchDrawable, NinePatchError};
+
+/// The patch margins of a Godot `NinePatchRect`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct GodotPatchMargins {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl GodotPatchMargins {
+    pub fn from_drawable(
+        drawable: &NinePatchDrawable,
+    ) -> Result<GodotPatchMargins, NinePatchError> {
+        let insets = drawable.slice_insets()?;
+        Ok(GodotPatchMargins {
+            left: insets.left.round() as i32,
+            top: insets.top.round() as i32,
+            right: insets.right.round() as i32,
+            bottom: insets.bottom.round() as i32,
+        })
+    }
+
+    /// Create a drawable for a `width` x `height` texture region. Godot has no
+    /// content padding, so the drawable has none.
+    pub fn to_drawable(
+        &self,
+        width: usize,
+        height: usize,
+    ) -> Result<NinePatchDrawable, NinePatchError> {
+        NinePatchBuilder::new(width, height)
+            .insets(Insets {
+                left: self.left as f32,
+                top: self.top as f32,
+                right: self.right as f32,
+                bottom: self.bottom as f32,
+            })
+            .build()
+    }
+}
+
+/// The `border` of a Unity sprite, `x` is the left border, `y` the bottom,
+/// `z` the right and `w` the top.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct UnitySpriteBorder {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl UnitySpriteBorder {
+    pub fn from_drawable(
+        drawable: &NinePatchDrawable,
+    ) -> Result<UnitySpriteBorder, NinePatchError> {
+        let insets = drawable.slice_insets()?;
+        Ok(UnitySpriteBorder {
+            x: insets.left,
+            y: insets.bottom,
+            z: insets.right,
+            w: insets.top,
+        })
+    }
+
+    /// Create a drawable for a `width` x `height` sprite. Unity has no content
+    /// padding, so the drawable has none.
+    pub fn to_drawable(
+        &self,
+        width: usize,
+        height: usize,
+    ) -> Result<NinePatchDrawable, NinePatchError> {
+        NinePatchBuilder::new(width, height)
+            .insets(Insets {
+                left: self.x,
+                top: self.w,
+                right: self.z,
+                bottom: self.y,
+            })
+            .build()
+    }
+
+    /// Read the `spriteBorder` of a single sprite texture `.meta` file, or
+    /// `None` if it has none.
+    pub fn parse_meta(meta: &str) -> Result<Option<UnitySpriteBorder>, NinePatchError> {
+        let Some((i, line)) = meta
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.trim_start().starts_with("spriteBorder:"))
+        else {
+            return Ok(None);
+        };
+        let syntax = NinePatchError::Syntax { line: i + 1 };
+        let value = line.split_once(':').unwrap().1.trim();
+        let fields = value
+            .strip_prefix('{')
+            .and_then(|v| v.strip_suffix('}'))
+            .ok_or(syntax.clone())?;
+        let mut border = UnitySpriteBorder::default();
+        for field in fields.split(',') {
+            let (key, value) = field.split_once(':').ok_or(syntax.clone())?;
+            let value: f32 = value.trim().parse().map_err(|_| syntax.clone())?;
+            match key.trim() {
+                "x" => border.x = value,
+                "y" => border.y = value,
+                "z" => border.z = value,
+                "w" => border.w = value,
+                _ => return Err(syntax),
+            }
+        }
+        Ok(Some(border))
+    }
+}
+
+/// A region of a libGDX texture packer atlas.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct AtlasRegion {
+    /// The file name of the page image the region is on.
+    pub page: String,
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// The `split` values: the left, right, top and bottom fixed borders.
+    pub split: Option<[i32; 4]>,
+    /// The `pad` values: the left, right, top and bottom content padding. A
+    /// value of -1 means the same as the corresponding split.
+    pub pad: Option<[i32; 4]>,
+}
+
+impl AtlasRegion {
+    /// Describe `drawable` as a region `name` of a page. The position on the
+    /// page is left at zero.
+    pub fn from_drawable(
+        page: &str,
+        name: &str,
+        drawable: &NinePatchDrawable,
+    ) -> Result<AtlasRegion, NinePatchError> {
+        let insets = drawable.slice_insets()?;
+        let round = |v: f32| v.round() as i32;
+        Ok(AtlasRegion {
+            page: page.to_string(),
+            name: name.to_string(),
+            width: drawable.width - 2,
+            height: drawable.height - 2,
+            split: Some([
+                round(insets.left),
+                round(insets.right),
+                round(insets.top),
+                round(insets.bottom),
+            ]),
+            pad: Some([
+                round(drawable.margin_left),
+                round(drawable.margin_right),
+                round(drawable.margin_top),
+                round(drawable.margin_bottom),
+            ]),
+            ..AtlasRegion::default()
+        })
+    }
+
+    /// Create a drawable for the region. A region without `split` has no
+    /// fixed borders and stretches as a whole.
+    pub fn to_drawable(&self) -> Result<NinePatchDrawable, NinePatchError> {
+        let mut builder = NinePatchBuilder::new(self.width, self.height);
+        if let Some([left, right, top, bottom]) = self.split {
+            builder = builder.insets(Insets {
+                left: left as f32,
+                top: top as f32,
+                right: right as f32,
+                bottom: bottom as f32,
+            });
+        }
+        if let Some(pad) = self.pad {
+            let split = self.split.unwrap_or_default();
+            let pad = |i: usize| if pad[i] == -1 { split[i] } else { pad[i] } as f32;
+            builder = builder.padding(Insets {
+                left: pad(0),
+                top: pad(2),
+                right: pad(1),
+                bottom: pad(3),
+            });
+        }
+        builder.build()
+    }
+}
+
+/// Parse the regions of a libGDX texture packer `.atlas` file, in either the
+/// legacy format with `xy` and `size` or the current one with `bounds`.
+pub fn parse_atlas(atlas: &str) -> Result<Vec<AtlasRegion>, NinePatchError> {
+    let mut regions: Vec<AtlasRegion> = vec![];
+    let mut page: Option<String> = None;
+    let mut in_page_header = false;
+    for (i, line) in atlas.lines().enumerate() {
+        let syntax = NinePatchError::Syntax { line: i + 1 };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            page = None;
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once(':') else {
+            match page {
+                None => {
+                    page = Some(trimmed.to_string());
+                    in_page_header = true;
+                }
+                Some(ref page) => {
+                    in_page_header = false;
+                    regions.push(AtlasRegion {
+                        page: page.clone(),
+                        name: trimmed.to_string(),
+                        ..AtlasRegion::default()
+                    });
+                }
+            }
+            continue;
+        };
+        if page.is_none() {
+            return Err(syntax);
+        }
+        if in_page_header {
+            continue;
+        }
+        let region = regions.last_mut().ok_or(syntax.clone())?;
+        let values = || -> Result<Vec<i32>, NinePatchError> {
+            value
+                .split(',')
+                .map(|v| v.trim().parse().map_err(|_| syntax.clone()))
+                .collect()
+        };
+        let unsigned = |v: i32| usize::try_from(v).map_err(|_| syntax.clone());
+        match key.trim() {
+            "xy" => {
+                let [x, y] = values()?[..] else {
+                    return Err(syntax);
+                };
+                (region.x, region.y) = (unsigned(x)?, unsigned(y)?);
+            }
+            "size" => {
+                let [width, height] = values()?[..] else {
+                    return Err(syntax);
+                };
+                (region.width, region.height) = (unsigned(width)?, unsigned(height)?);
+            }
+            "bounds" => {
+                let [x, y, width, height] = values()?[..] else {
+                    return Err(syntax);
+                };
+                (region.x, region.y) = (unsigned(x)?, unsigned(y)?);
+                (region.width, region.height) = (unsigned(width)?, unsigned(height)?);
+            }
+            "split" => region.split = Some(values()?.try_into().map_err(|_| syntax)?),
+            "pad" => region.pad = Some(values()?.try_into().map_err(|_| syntax)?),
+            _ => {}
+        }
+    }
+    Ok(regions)
+}
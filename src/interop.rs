@@ -0,0 +1,268 @@
+//! Conversions to and from the geometry types of other crates, each behind
+//! the feature of the same name. Patches convert to their source and target
+//! rectangles, so a whole [`crate::NinePatchLayout`] converts through
+//! [`crate::NinePatchLayout::patches`].
+
+#[cfg(feature = "euclid")]
+mod euclid_impls {
+    use std::ops::{Add, Sub};
+
+    use euclid::{point2, size2, Box2D, Rect as EuclidRect, SideOffsets2D};
+
+    use crate::{Insets, Patch, Rect};
+
+    impl<T, U> From<Rect<T>> for Box2D<T, U> {
+        fn from(rect: Rect<T>) -> Box2D<T, U> {
+            Box2D::new(point2(rect.left, rect.top), point2(rect.right, rect.bottom))
+        }
+    }
+
+    impl<T, U> From<Box2D<T, U>> for Rect<T> {
+        fn from(rect: Box2D<T, U>) -> Rect<T> {
+            Rect {
+                left: rect.min.x,
+                top: rect.min.y,
+                right: rect.max.x,
+                bottom: rect.max.y,
+            }
+        }
+    }
+
+    impl<T: Copy + Sub<Output = T>, U> From<Rect<T>> for EuclidRect<T, U> {
+        fn from(rect: Rect<T>) -> EuclidRect<T, U> {
+            EuclidRect::new(
+                point2(rect.left, rect.top),
+                size2(rect.right - rect.left, rect.bottom - rect.top),
+            )
+        }
+    }
+
+    impl<T: Copy + Add<Output = T>, U> From<EuclidRect<T, U>> for Rect<T> {
+        fn from(rect: EuclidRect<T, U>) -> Rect<T> {
+            Rect {
+                left: rect.origin.x,
+                top: rect.origin.y,
+                right: rect.origin.x + rect.size.width,
+                bottom: rect.origin.y + rect.size.height,
+            }
+        }
+    }
+
+    /// The source and target rectangles.
+    impl<T, U> From<Patch<T>> for (Box2D<T, U>, Box2D<T, U>) {
+        fn from(patch: Patch<T>) -> (Box2D<T, U>, Box2D<T, U>) {
+            (patch.source.into(), patch.target.into())
+        }
+    }
+
+    impl<U> From<Insets> for SideOffsets2D<f32, U> {
+        fn from(insets: Insets) -> SideOffsets2D<f32, U> {
+            SideOffsets2D::new(insets.top, insets.right, insets.bottom, insets.left)
+        }
+    }
+
+    impl<U> From<SideOffsets2D<f32, U>> for Insets {
+        fn from(offsets: SideOffsets2D<f32, U>) -> Insets {
+            Insets {
+                left: offsets.left,
+                top: offsets.top,
+                right: offsets.right,
+                bottom: offsets.bottom,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use glam::{DVec2, DVec4, Vec2, Vec4};
+
+    use crate::{Patch, Rect};
+
+    /// The left, top, right and bottom edges.
+    impl From<Rect<f32>> for Vec4 {
+        fn from(rect: Rect<f32>) -> Vec4 {
+            Vec4::new(rect.left, rect.top, rect.right, rect.bottom)
+        }
+    }
+
+    impl From<Vec4> for Rect<f32> {
+        fn from(edges: Vec4) -> Rect<f32> {
+            Rect {
+                left: edges.x,
+                top: edges.y,
+                right: edges.z,
+                bottom: edges.w,
+            }
+        }
+    }
+
+    /// The top left and bottom right corners.
+    impl From<Rect<f32>> for (Vec2, Vec2) {
+        fn from(rect: Rect<f32>) -> (Vec2, Vec2) {
+            (
+                Vec2::new(rect.left, rect.top),
+                Vec2::new(rect.right, rect.bottom),
+            )
+        }
+    }
+
+    impl From<(Vec2, Vec2)> for Rect<f32> {
+        fn from((min, max): (Vec2, Vec2)) -> Rect<f32> {
+            Rect {
+                left: min.x,
+                top: min.y,
+                right: max.x,
+                bottom: max.y,
+            }
+        }
+    }
+
+    /// The edges of the source and target rectangles.
+    impl From<Patch<f32>> for (Vec4, Vec4) {
+        fn from(patch: Patch<f32>) -> (Vec4, Vec4) {
+            (patch.source.into(), patch.target.into())
+        }
+    }
+
+    impl From<Rect<f64>> for DVec4 {
+        fn from(rect: Rect<f64>) -> DVec4 {
+            DVec4::new(rect.left, rect.top, rect.right, rect.bottom)
+        }
+    }
+
+    impl From<DVec4> for Rect<f64> {
+        fn from(edges: DVec4) -> Rect<f64> {
+            Rect {
+                left: edges.x,
+                top: edges.y,
+                right: edges.z,
+                bottom: edges.w,
+            }
+        }
+    }
+
+    impl From<Rect<f64>> for (DVec2, DVec2) {
+        fn from(rect: Rect<f64>) -> (DVec2, DVec2) {
+            (
+                DVec2::new(rect.left, rect.top),
+                DVec2::new(rect.right, rect.bottom),
+            )
+        }
+    }
+
+    impl From<(DVec2, DVec2)> for Rect<f64> {
+        fn from((min, max): (DVec2, DVec2)) -> Rect<f64> {
+            Rect {
+                left: min.x,
+                top: min.y,
+                right: max.x,
+                bottom: max.y,
+            }
+        }
+    }
+
+    impl From<Patch<f64>> for (DVec4, DVec4) {
+        fn from(patch: Patch<f64>) -> (DVec4, DVec4) {
+            (patch.source.into(), patch.target.into())
+        }
+    }
+}
+
+#[cfg(feature = "kurbo")]
+mod kurbo_impls {
+    use crate::{Insets, Patch, Rect, Scalar};
+
+    impl<T: Scalar> From<Rect<T>> for kurbo::Rect {
+        fn from(rect: Rect<T>) -> kurbo::Rect {
+            kurbo::Rect::new(
+                rect.left.to_f64(),
+                rect.top.to_f64(),
+                rect.right.to_f64(),
+                rect.bottom.to_f64(),
+            )
+        }
+    }
+
+    /// Rounds each edge for integer coordinates.
+    impl<T: Scalar> From<kurbo::Rect> for Rect<T> {
+        fn from(rect: kurbo::Rect) -> Rect<T> {
+            Rect {
+                left: T::from_f64(rect.x0),
+                top: T::from_f64(rect.y0),
+                right: T::from_f64(rect.x1),
+                bottom: T::from_f64(rect.y1),
+            }
+        }
+    }
+
+    /// The source and target rectangles.
+    impl<T: Scalar> From<Patch<T>> for (kurbo::Rect, kurbo::Rect) {
+        fn from(patch: Patch<T>) -> (kurbo::Rect, kurbo::Rect) {
+            (patch.source.into(), patch.target.into())
+        }
+    }
+
+    impl From<Insets> for kurbo::Insets {
+        fn from(insets: Insets) -> kurbo::Insets {
+            kurbo::Insets::new(
+                insets.left.into(),
+                insets.top.into(),
+                insets.right.into(),
+                insets.bottom.into(),
+            )
+        }
+    }
+
+    impl From<kurbo::Insets> for Insets {
+        fn from(insets: kurbo::Insets) -> Insets {
+            Insets {
+                left: insets.x0 as f32,
+                top: insets.y0 as f32,
+                right: insets.x1 as f32,
+                bottom: insets.y1 as f32,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+mod mint_impls {
+    use mint::Point2;
+
+    use crate::{Patch, Rect};
+
+    /// The top left and bottom right corners.
+    impl<T> From<Rect<T>> for (Point2<T>, Point2<T>) {
+        fn from(rect: Rect<T>) -> (Point2<T>, Point2<T>) {
+            (
+                Point2 {
+                    x: rect.left,
+                    y: rect.top,
+                },
+                Point2 {
+                    x: rect.right,
+                    y: rect.bottom,
+                },
+            )
+        }
+    }
+
+    impl<T> From<(Point2<T>, Point2<T>)> for Rect<T> {
+        fn from((min, max): (Point2<T>, Point2<T>)) -> Rect<T> {
+            Rect {
+                left: min.x,
+                top: min.y,
+                right: max.x,
+                bottom: max.y,
+            }
+        }
+    }
+
+    /// The corners of the source and target rectangles.
+    impl<T> From<Patch<T>> for ((Point2<T>, Point2<T>), (Point2<T>, Point2<T>)) {
+        fn from(patch: Patch<T>) -> ((Point2<T>, Point2<T>), (Point2<T>, Point2<T>)) {
+            (patch.source.into(), patch.target.into())
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use crate::patches::AxisScale;
+use crate::{
+    Axis, NinePatchDrawable, NinePatchError, Patch, PatchKind, Rect, Scalar, ScaleOptions, Section,
+};
+
+impl NinePatchDrawable {
+    /// Like [`NinePatchDrawable::scale_to_with`], with any coordinate type.
+    ///
+    /// With integer coordinates the edges are rounded to whole pixels, which
+    /// suits integer rendering pipelines. `f64` keeps sub pixel precision for
+    /// targets larger than `f32` can represent exactly.
+    pub fn scale_to_as<T: Scalar>(
+        &self,
+        width: usize,
+        height: usize,
+        options: &ScaleOptions,
+    ) -> Result<Vec<Patch<T>>, NinePatchError> {
+        Ok(
+            NinePatchLayout::<T>::from_drawable(self, width, height, options)?
+                .patches()
+                .collect(),
+        )
+    }
+}
+
+/// The patches of a drawable scaled to one target size, stored as the edges
+/// of its columns and rows.
+///
+/// A layout is computed once for a size and can then be drawn any number of
+/// times, see [`LayoutCache`] for widgets that redraw at the same sizes.
+///
+/// Every [`Patch`] of the layout is derived from the edge arrays, so the
+/// right edge of a patch is exactly the left edge of the next one and grid
+/// renderers can use the edges directly.
+///
+/// The coordinates are `f32` by default, see [`Scalar`] for the others.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NinePatchLayout<T = f32> {
+    width: usize,
+    height: usize,
+    source_columns: Vec<T>,
+    source_rows: Vec<T>,
+    columns: Vec<T>,
+    rows: Vec<T>,
+    h_kinds: Vec<PatchKind>,
+    v_kinds: Vec<PatchKind>,
+}
+
+impl NinePatchLayout {
+    /// Lay out `drawable` into a `width` x `height` target, with the same
+    /// rules as [`NinePatchDrawable::scale_to_with`].
+    pub fn new(
+        drawable: &NinePatchDrawable,
+        width: usize,
+        height: usize,
+        options: &ScaleOptions,
+    ) -> Result<NinePatchLayout, NinePatchError> {
+        NinePatchLayout::from_drawable(drawable, width, height, options)
+    }
+}
+
+impl<T: Scalar> NinePatchLayout<T> {
+    /// Like [`NinePatchLayout::new`], with any coordinate type.
+    pub fn from_drawable(
+        drawable: &NinePatchDrawable,
+        width: usize,
+        height: usize,
+        options: &ScaleOptions,
+    ) -> Result<NinePatchLayout<T>, NinePatchError> {
+        let h_scale = AxisScale::new(
+            Axis::Horizontal,
+            &drawable.h_sections,
+            drawable.width,
+            width,
+            options,
+        )?;
+        let v_scale = AxisScale::new(
+            Axis::Vertical,
+            &drawable.v_sections,
+            drawable.height,
+            height,
+            options,
+        )?;
+        let edges = |sections: &[Section], scale: &AxisScale| {
+            let mut source = vec![];
+            let mut target = vec![];
+            let mut edge = scale.origin;
+            for (i, section) in sections.iter().enumerate() {
+                source.push(T::from_f64(f64::from(section.start) + 1.0));
+                target.push(T::from_f64(edge));
+                edge += scale.len(sections, i);
+            }
+            if let Some(last) = sections.last() {
+                source.push(T::from_f64(f64::from(last.start + last.len) + 1.0));
+                target.push(T::from_f64(edge));
+            }
+            (source, target)
+        };
+        let (source_columns, columns) = edges(&drawable.h_sections, &h_scale);
+        let (source_rows, rows) = edges(&drawable.v_sections, &v_scale);
+        Ok(NinePatchLayout {
+            width,
+            height,
+            source_columns,
+            source_rows,
+            columns,
+            rows,
+            h_kinds: drawable.h_sections.iter().map(|s| s.kind).collect(),
+            v_kinds: drawable.v_sections.iter().map(|s| s.kind).collect(),
+        })
+    }
+
+    /// The target width the layout was computed for.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The target height the layout was computed for.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of patch columns, one per horizontal section.
+    pub fn columns(&self) -> usize {
+        self.h_kinds.len()
+    }
+
+    /// The number of patch rows, one per vertical section.
+    pub fn rows(&self) -> usize {
+        self.v_kinds.len()
+    }
+
+    /// The x coordinates of the column boundaries in the target, one more
+    /// than the number of columns. Empty if the drawable has no sections.
+    pub fn column_edges(&self) -> &[T] {
+        &self.columns
+    }
+
+    /// The y coordinates of the row boundaries in the target, one more than
+    /// the number of rows. Empty if the drawable has no sections.
+    pub fn row_edges(&self) -> &[T] {
+        &self.rows
+    }
+
+    /// The x coordinates of the column boundaries in the nine patch bitmap.
+    pub fn source_column_edges(&self) -> &[T] {
+        &self.source_columns
+    }
+
+    /// The y coordinates of the row boundaries in the nine patch bitmap.
+    pub fn source_row_edges(&self) -> &[T] {
+        &self.source_rows
+    }
+
+    /// How each column is scaled horizontally.
+    pub fn column_kinds(&self) -> &[PatchKind] {
+        &self.h_kinds
+    }
+
+    /// How each row is scaled vertically.
+    pub fn row_kinds(&self) -> &[PatchKind] {
+        &self.v_kinds
+    }
+
+    /// The patch in `column` and `row`, or `None` if out of range.
+    pub fn patch(&self, column: usize, row: usize) -> Option<Patch<T>> {
+        let h_kind = *self.h_kinds.get(column)?;
+        let v_kind = *self.v_kinds.get(row)?;
+        Some(Patch {
+            source: Rect {
+                left: self.source_columns[column],
+                top: self.source_rows[row],
+                right: self.source_columns[column + 1],
+                bottom: self.source_rows[row + 1],
+            },
+            target: Rect {
+                left: self.columns[column],
+                top: self.rows[row],
+                right: self.columns[column + 1],
+                bottom: self.rows[row + 1],
+            },
+            h_kind,
+            v_kind,
+        })
+    }
+
+    /// Iterate over the patches in row major order, the same order as
+    /// [`NinePatchDrawable::scale_to`].
+    pub fn patches(&self) -> impl ExactSizeIterator<Item = Patch<T>> + '_ {
+        let columns = self.columns();
+        (0..self.rows() * columns).map(move |i| {
+            self.patch(i % columns, i / columns)
+                .expect("index is within the grid")
+        })
+    }
+}
+
+/// A least recently used cache of the layouts of one drawable, keyed by
+/// target size.
+///
+/// Layouts are handed out as [`Arc`]s so they can be kept and shared after
+/// being evicted from the cache.
+#[derive(Debug)]
+pub struct LayoutCache {
+    drawable: NinePatchDrawable,
+    options: ScaleOptions,
+    capacity: usize,
+    /// Most recently used last.
+    layouts: Vec<Arc<NinePatchLayout>>,
+}
+
+impl LayoutCache {
+    /// Create a cache keeping up to `capacity` layouts of `drawable`.
+    pub fn new(drawable: NinePatchDrawable, options: ScaleOptions, capacity: usize) -> LayoutCache {
+        LayoutCache {
+            drawable,
+            options,
+            capacity,
+            layouts: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn drawable(&self) -> &NinePatchDrawable {
+        &self.drawable
+    }
+
+    pub fn options(&self) -> &ScaleOptions {
+        &self.options
+    }
+
+    /// The layout for a `width` x `height` target, computed if it is not
+    /// cached yet. The least recently used layout is evicted when the cache
+    /// is full. Errors are not cached.
+    pub fn get(
+        &mut self,
+        width: usize,
+        height: usize,
+    ) -> Result<Arc<NinePatchLayout>, NinePatchError> {
+        if let Some(i) = self
+            .layouts
+            .iter()
+            .position(|l| l.width == width && l.height == height)
+        {
+            let layout = self.layouts.remove(i);
+            self.layouts.push(layout.clone());
+            return Ok(layout);
+        }
+        let layout = Arc::new(NinePatchLayout::new(
+            &self.drawable,
+            width,
+            height,
+            &self.options,
+        )?);
+        if self.capacity == 0 {
+            return Ok(layout);
+        }
+        if self.layouts.len() == self.capacity {
+            self.layouts.remove(0);
+        }
+        self.layouts.push(layout.clone());
+        Ok(layout)
+    }
+
+    /// The number of cached layouts.
+    pub fn len(&self) -> usize {
+        self.layouts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layouts.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.layouts.clear();
+    }
+}
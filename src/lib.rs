@@ -6,15 +6,91 @@
 //! [1]: https://developer.android.com/develop/ui/views/graphics/drawables#nine-patch
 
 use std::fmt::Display;
+use std::sync::Arc;
 
-#[derive(Debug, PartialEq)]
-pub struct RectF {
+pub use builder::NinePatchBuilder;
+pub use css::{html_preview, to_css};
+pub use damage::damaged_rects;
+pub use debug::render_debug;
+pub use detect::{detect_slices, mark_up, SliceDetection};
+pub use distribution::{CenterOnly, EqualShare, Proportional, StretchPolicy, StretchWeights};
+pub use engines::{parse_atlas, AtlasRegion, GodotPatchMargins, UnitySpriteBorder};
+pub use layout::{LayoutCache, NinePatchLayout};
+pub use lint::{lint, LintWarning};
+pub use patches::Patches;
+pub use render::{filter_content, render, RenderOptions};
+pub use scalar::Scalar;
+pub use set::{parse_density, NinePatchSet};
+#[cfg(feature = "tiny-skia")]
+pub use skia::draw_nine_patch;
+pub use svg::to_svg;
+pub use tint::{ColorMatrix, PixelFormat, Tint, TintMode};
+pub use transform::{mirror_bitmap, rotate_bitmap};
+pub use transition::NinePatchTransition;
+pub use xml::NinePatchResource;
+
+mod builder;
+mod css;
+mod damage;
+mod debug;
+mod detect;
+mod distribution;
+mod engines;
+#[cfg(any(
+    feature = "euclid",
+    feature = "glam",
+    feature = "kurbo",
+    feature = "mint"
+))]
+mod interop;
+mod layout;
+mod lint;
+mod patches;
+mod render;
+mod scalar;
+mod set;
+#[cfg(feature = "tiny-skia")]
+mod skia;
+mod svg;
+mod tint;
+mod transform;
+mod transition;
+mod xml;
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Rect<T = f32> {
+    pub left: T,
+    pub top: T,
+    pub right: T,
+    pub bottom: T,
+}
+
+pub type RectF = Rect<f32>;
+
+/// Distances from the edges of an image, in pixels.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Insets {
     pub left: f32,
     pub top: f32,
     pub right: f32,
     pub bottom: f32,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Display for Axis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Axis::Horizontal => write!(f, "horizontal"),
+            Axis::Vertical => write!(f, "vertical"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PatchKind {
     Unknown,
@@ -23,22 +99,71 @@ pub enum PatchKind {
     Tiling,
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Section {
-    pub start: f32,
-    pub len: f32,
+#[derive(Debug, PartialEq, Clone)]
+pub struct Section<T = f32> {
+    pub start: T,
+    pub len: T,
     pub kind: PatchKind,
 }
 
 #[derive(Debug, PartialEq)]
-pub struct Patch {
-    pub source: RectF,
-    pub target: RectF,
+pub struct Patch<T = f32> {
+    pub source: Rect<T>,
+    pub target: Rect<T>,
     pub h_kind: PatchKind,
     pub v_kind: PatchKind,
 }
 
-#[derive(Debug)]
+/// What to do with an axis that has no stretching sections.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum StretchFallback {
+    /// Stretch the whole axis, like Android does.
+    #[default]
+    StretchAll,
+    /// Keep the axis at its source size and center it in the target.
+    Center,
+    /// Fail with [`NinePatchError::NoStretchSection`].
+    Error,
+}
+
+/// Options for [`NinePatchDrawable::scale_to_with`].
+#[derive(Debug, Clone)]
+pub struct ScaleOptions {
+    pub fallback: StretchFallback,
+    /// Ratio of the target density to the source density. Fixed sections and
+    /// margins are scaled by this factor, the stretching sections fill the
+    /// rest of the target. Must be positive and finite, or scaling fails with
+    /// [`NinePatchError::InvalidDensity`].
+    pub density: f32,
+    /// How the stretching sections share the growth beyond their source
+    /// length, [`Proportional`] if `None`.
+    pub distribution: Option<Arc<dyn StretchPolicy>>,
+}
+
+impl Default for ScaleOptions {
+    fn default() -> ScaleOptions {
+        ScaleOptions {
+            fallback: StretchFallback::default(),
+            density: 1.0,
+            distribution: None,
+        }
+    }
+}
+
+/// Distribution policies are equal if they are the same instance.
+impl PartialEq for ScaleOptions {
+    fn eq(&self, other: &ScaleOptions) -> bool {
+        self.fallback == other.fallback
+            && self.density == other.density
+            && match (&self.distribution, &other.distribution) {
+                (None, None) => true,
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                _ => false,
+            }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct NinePatchDrawable {
     pub width: usize,
     pub height: usize,
@@ -48,12 +173,76 @@ pub struct NinePatchDrawable {
     pub margin_top: f32,
     pub margin_right: f32,
     pub margin_bottom: f32,
+    /// Optical bounds insets, marked by red pixels at the ends of the right
+    /// and bottom borders. Also known as layout bounds.
+    pub optical_insets: Insets,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum NinePatchError {
     InvalidBitmap,
     InvalidMargin,
+    /// The section at `index` is empty or has a negative length.
+    EmptySection {
+        axis: Axis,
+        index: usize,
+    },
+    /// The section at `index` overlaps the section before it.
+    SectionOverlap {
+        axis: Axis,
+        index: usize,
+    },
+    /// The section at `index` extends past the edge of the image.
+    SectionOutOfBounds {
+        axis: Axis,
+        index: usize,
+    },
+    /// There is a gap between the section at `index` and the one before it.
+    SectionGap {
+        axis: Axis,
+        index: usize,
+    },
+    /// The sections do not cover the whole image.
+    IncompleteCoverage {
+        axis: Axis,
+    },
+    /// The axis has no stretching section and the scale options ask for an
+    /// error in that case.
+    NoStretchSection {
+        axis: Axis,
+    },
+    /// The target is too small to hold the fixed sections.
+    TargetTooSmall {
+        axis: Axis,
+    },
+    /// The density is not a positive number.
+    InvalidDensity,
+    /// The sections of two drawables do not correspond to each other.
+    MismatchedSections {
+        axis: Axis,
+    },
+    /// The axis does not have exactly one stretching section, so it cannot
+    /// be described by a three by three grid.
+    UnsupportedSections {
+        axis: Axis,
+    },
+    /// A metadata file could not be parsed. Lines are numbered from 1.
+    Syntax {
+        line: usize,
+    },
+    /// The resource has an element that is not supported.
+    UnsupportedElement(String),
+    /// The resource has an attribute that is not supported.
+    UnsupportedAttribute(String),
+    /// The resource lacks a required attribute.
+    MissingAttribute(String),
+    /// An attribute refers to another resource or to a theme attribute, such
+    /// as `@color/accent` or `?attr/colorAccent`, which has to be resolved
+    /// before parsing.
+    UnresolvedReference {
+        attribute: String,
+        reference: String,
+    },
 }
 
 impl Display for NinePatchError {
@@ -61,6 +250,44 @@ impl Display for NinePatchError {
         match self {
             NinePatchError::InvalidBitmap => write!(f, "invalid bitmap"),
             NinePatchError::InvalidMargin => write!(f, "invalid margin"),
+            NinePatchError::EmptySection { axis, index } => {
+                write!(f, "{axis} section {index} is empty")
+            }
+            NinePatchError::SectionOverlap { axis, index } => {
+                write!(f, "{axis} section {index} overlaps the previous section")
+            }
+            NinePatchError::SectionOutOfBounds { axis, index } => {
+                write!(f, "{axis} section {index} is out of bounds")
+            }
+            NinePatchError::SectionGap { axis, index } => {
+                write!(f, "gap before {axis} section {index}")
+            }
+            NinePatchError::IncompleteCoverage { axis } => {
+                write!(f, "{axis} sections do not cover the image")
+            }
+            NinePatchError::NoStretchSection { axis } => {
+                write!(f, "no {axis} stretching section")
+            }
+            NinePatchError::TargetTooSmall { axis } => {
+                write!(f, "target is too small for the {axis} fixed sections")
+            }
+            NinePatchError::InvalidDensity => write!(f, "invalid density"),
+            NinePatchError::MismatchedSections { axis } => {
+                write!(f, "{axis} sections do not match")
+            }
+            NinePatchError::UnsupportedSections { axis } => {
+                write!(f, "{axis} sections do not form a three by three grid")
+            }
+            NinePatchError::Syntax { line } => write!(f, "syntax error on line {line}"),
+            NinePatchError::UnsupportedElement(name) => write!(f, "unsupported element {name}"),
+            NinePatchError::UnsupportedAttribute(name) => {
+                write!(f, "unsupported attribute {name}")
+            }
+            NinePatchError::MissingAttribute(name) => write!(f, "missing attribute {name}"),
+            NinePatchError::UnresolvedReference {
+                attribute,
+                reference,
+            } => write!(f, "{attribute} refers to unresolved {reference}"),
         }
     }
 }
@@ -68,6 +295,9 @@ impl Display for NinePatchError {
 impl NinePatchDrawable {
     /// Create a new nine patch drawable from a bitmap. The pixel format can be
     /// either RGBA(8) or BGRA(8).
+    ///
+    /// Bitmaps written by Android tools go through
+    /// [`clear_transparent_border`] first.
     pub fn new(
         bitmap: &[u8],
         stride: usize,
@@ -78,15 +308,15 @@ impl NinePatchDrawable {
             return Err(NinePatchError::InvalidBitmap);
         }
 
-        let top_sections = h_sections(bitmap, 0, width);
-        let left_sections = v_sections(bitmap, 0, stride, height);
-        let right_sections = dbg!(v_sections(bitmap, (width - 1) * 4, stride, height));
-        let bottom_sections = dbg!(h_sections(bitmap, (height - 1) * stride, width));
+        let top_sections = h_sections(bitmap, 0, width, false);
+        let left_sections = v_sections(bitmap, 0, stride, height, false);
+        let right_sections = v_sections(bitmap, (width - 1) * 4, stride, height, true);
+        let bottom_sections = h_sections(bitmap, (height - 1) * stride, width, true);
 
-        if right_sections.len() != 0 && right_sections.len() != 3 {
+        if !right_sections.is_empty() && right_sections.len() != 3 {
             return Err(NinePatchError::InvalidMargin);
         }
-        if bottom_sections.len() != 0 && bottom_sections.len() != 3 {
+        if !bottom_sections.is_empty() && bottom_sections.len() != 3 {
             return Err(NinePatchError::InvalidMargin);
         }
 
@@ -99,68 +329,205 @@ impl NinePatchDrawable {
             margin_top: right_sections.first().map_or(0.0, |s| s.len),
             margin_right: bottom_sections.last().map_or(0.0, |s| s.len),
             margin_bottom: right_sections.last().map_or(0.0, |s| s.len),
+            optical_insets: Insets {
+                left: optical_run(bitmap, (height - 1) * stride + 4, 4, width - 2),
+                top: optical_run(
+                    bitmap,
+                    stride + (width - 1) * 4,
+                    stride as isize,
+                    height - 2,
+                ),
+                right: optical_run(
+                    bitmap,
+                    (height - 1) * stride + (width - 2) * 4,
+                    -4,
+                    width - 2,
+                ),
+                bottom: optical_run(
+                    bitmap,
+                    (height - 2) * stride + (width - 1) * 4,
+                    -(stride as isize),
+                    height - 2,
+                ),
+            },
         })
     }
 
+    /// Create a new nine patch drawable from explicit sections. `width` and
+    /// `height` include the one pixel marker border, like the size passed to
+    /// [`NinePatchDrawable::new`].
+    ///
+    /// The result is checked with [`NinePatchDrawable::validate`].
+    pub fn from_sections(
+        width: usize,
+        height: usize,
+        h_sections: Vec<Section>,
+        v_sections: Vec<Section>,
+        padding: Insets,
+    ) -> Result<NinePatchDrawable, NinePatchError> {
+        let drawable = NinePatchDrawable {
+            width,
+            height,
+            h_sections,
+            v_sections,
+            margin_left: padding.left,
+            margin_top: padding.top,
+            margin_right: padding.right,
+            margin_bottom: padding.bottom,
+            optical_insets: Insets::default(),
+        };
+        drawable.validate()?;
+        Ok(drawable)
+    }
+
+    /// Check that the sections are ordered, do not overlap, have positive
+    /// lengths and cover the image between the marker borders, and that the
+    /// margins are finite and fit in the image.
+    pub fn validate(&self) -> Result<(), NinePatchError> {
+        if self.width < 3 || self.height < 3 {
+            return Err(NinePatchError::InvalidBitmap);
+        }
+        validate_sections(Axis::Horizontal, &self.h_sections, self.width as f32 - 2.0)?;
+        validate_sections(Axis::Vertical, &self.v_sections, self.height as f32 - 2.0)?;
+        let invalid = |margin: f32| !margin.is_finite() || margin < 0.0;
+        if invalid(self.margin_left)
+            || invalid(self.margin_top)
+            || invalid(self.margin_right)
+            || invalid(self.margin_bottom)
+            || self.margin_left + self.margin_right > self.width as f32 - 2.0
+            || self.margin_top + self.margin_bottom > self.height as f32 - 2.0
+        {
+            return Err(NinePatchError::InvalidMargin);
+        }
+        Ok(())
+    }
+
     pub fn scale_to(&self, width: usize, height: usize) -> Vec<Patch> {
         assert!(width >= self.width && height >= self.height);
 
-        let stretching_width: f32 = self
-            .h_sections
-            .iter()
-            .filter(|s| s.kind != PatchKind::Fixed)
-            .map(|s| s.len)
-            .sum();
-        let stretching_height: f32 = self
-            .v_sections
-            .iter()
-            .filter(|s| s.kind != PatchKind::Fixed)
-            .map(|s| s.len)
-            .sum();
-        let fixed_width = self.width as f32 - stretching_width;
-        let fixed_height = self.height as f32 - stretching_height;
-        let mut left = 1.0;
-        let mut top = 1.0;
-        let mut prev_bottom = 1.0;
-        let mut patches = vec![];
-        for v in &self.v_sections {
-            for h in &self.h_sections {
-                let source = RectF {
-                    left: h.start + 1.0,
-                    top: v.start + 1.0,
-                    right: h.start + 1.0 + h.len,
-                    bottom: v.start + 1.0 + v.len,
-                };
-                let right = match h.kind {
-                    PatchKind::Fixed => left + h.len,
-                    _ => left + (h.len / stretching_width) * (width as f32 - fixed_width),
-                };
-                let bottom = match v.kind {
-                    PatchKind::Fixed => top + v.len,
-                    _ => top + (v.len / stretching_height) * (height as f32 - fixed_height),
-                };
-                patches.push(Patch {
-                    source,
-                    target: RectF {
-                        left,
-                        top,
-                        right,
-                        bottom,
-                    },
-                    h_kind: h.kind,
-                    v_kind: v.kind,
-                });
-                left = right;
-                prev_bottom = bottom;
-            }
-            left = 1.0;
-            top = prev_bottom;
+        self.scale_to_with(width, height, &ScaleOptions::default())
+            .expect("default scale options never fail")
+    }
+
+    /// The sizes of the fixed borders of a drawable with exactly one
+    /// stretching section per axis, the inverse of
+    /// [`NinePatchBuilder::insets`].
+    pub fn slice_insets(&self) -> Result<Insets, NinePatchError> {
+        let (left, right) = slice_insets(Axis::Horizontal, &self.h_sections)?;
+        let (top, bottom) = slice_insets(Axis::Vertical, &self.v_sections)?;
+        Ok(Insets {
+            left,
+            top,
+            right,
+            bottom,
+        })
+    }
+
+    /// The area available to content when scaled to `width` x `height`, in the
+    /// same coordinates as the patch targets.
+    pub fn content_rect(&self, width: usize, height: usize, options: &ScaleOptions) -> RectF {
+        RectF {
+            left: 1.0 + self.margin_left * options.density,
+            top: 1.0 + self.margin_top * options.density,
+            right: width as f32 - 1.0 - self.margin_right * options.density,
+            bottom: height as f32 - 1.0 - self.margin_bottom * options.density,
+        }
+    }
+
+    /// Like [`NinePatchDrawable::scale_to`], with explicit [`ScaleOptions`].
+    pub fn scale_to_with(
+        &self,
+        width: usize,
+        height: usize,
+        options: &ScaleOptions,
+    ) -> Result<Vec<Patch>, NinePatchError> {
+        Ok(self.patches_with(width, height, options)?.collect())
+    }
+}
+
+/// Turn the fully transparent pixels of the marker border white, so that
+/// [`NinePatchDrawable::new`] reads them as unmarked.
+///
+/// The parser takes any colour but white for a marker, whatever its alpha,
+/// while Android tools only take opaque pixels and write the rest of the
+/// border as transparent black. Apply this to bitmaps from those tools
+/// before parsing them.
+pub fn clear_transparent_border(
+    bitmap: &mut [u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+) -> Result<(), NinePatchError> {
+    if bitmap.len() != stride * height || stride < width * 4 || width < 3 || height < 3 {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    let border = (0..width)
+        .flat_map(|x| [(x, 0), (x, height - 1)])
+        .chain((1..height - 1).flat_map(|y| [(0, y), (width - 1, y)]));
+    for (x, y) in border {
+        let o = y * stride + x * 4;
+        if bitmap[o + 3] == 0 {
+            bitmap[o..o + 4].copy_from_slice(&[0xFF, 0xFF, 0xFF, 0x00]);
         }
-        patches
     }
+    Ok(())
+}
+
+fn slice_insets(axis: Axis, sections: &[Section]) -> Result<(f32, f32), NinePatchError> {
+    let mut stretching = sections.iter().filter(|s| s.kind != PatchKind::Fixed);
+    match (stretching.next(), stretching.next()) {
+        (Some(center), None) => {
+            let end: f32 = sections.iter().map(|s| s.len).sum();
+            Ok((center.start, end - center.start - center.len))
+        }
+        _ => Err(NinePatchError::UnsupportedSections { axis }),
+    }
+}
+
+fn validate_sections(axis: Axis, sections: &[Section], len: f32) -> Result<(), NinePatchError> {
+    let mut cursor = 0.0;
+    for (index, section) in sections.iter().enumerate() {
+        if section.len.is_nan() || section.len <= 0.0 {
+            return Err(NinePatchError::EmptySection { axis, index });
+        }
+        if section.start < cursor {
+            return Err(NinePatchError::SectionOverlap { axis, index });
+        }
+        if section.start > cursor {
+            return Err(NinePatchError::SectionGap { axis, index });
+        }
+        if section.start + section.len > len {
+            return Err(NinePatchError::SectionOutOfBounds { axis, index });
+        }
+        cursor = section.start + section.len;
+    }
+    if cursor != len {
+        return Err(NinePatchError::IncompleteCoverage { axis });
+    }
+    Ok(())
+}
+
+/// Whether the pixel at `o` is a layout bounds marker, red in either RGBA or
+/// BGRA.
+fn is_optical_marker(bitmap: &[u8], o: usize) -> bool {
+    matches!(
+        (bitmap[o], bitmap[o + 1], bitmap[o + 2]),
+        (0xFF, 0x00, 0x00) | (0x00, 0x00, 0xFF)
+    )
+}
+
+/// The number of consecutive layout bounds markers starting at `offset`.
+fn optical_run(bitmap: &[u8], offset: usize, advance: isize, len: usize) -> f32 {
+    (0..len)
+        .take_while(|&i| {
+            is_optical_marker(bitmap, offset.wrapping_add_signed(advance * i as isize))
+        })
+        .count() as f32
 }
 
-fn h_sections(bitmap: &[u8], offset: usize, width: usize) -> Vec<Section> {
+/// Scan a horizontal border. Layout bounds markers only exist on the padding
+/// borders, where `optical` makes them count as unmarked.
+fn h_sections(bitmap: &[u8], offset: usize, width: usize, optical: bool) -> Vec<Section> {
     let mut start = 0.0;
     let mut len = 0.0;
     let mut kind = PatchKind::Unknown;
@@ -169,6 +536,7 @@ fn h_sections(bitmap: &[u8], offset: usize, width: usize) -> Vec<Section> {
         let o = offset + i * 4;
         let i_kind = match (bitmap[o], bitmap[o + 1], bitmap[o + 2]) {
             (0xFF, 0xFF, 0xFF) => PatchKind::Fixed,
+            _ if optical && is_optical_marker(bitmap, o) => PatchKind::Fixed,
             _ => PatchKind::Stretching,
         };
         if i != (width - 1) && (kind == i_kind || kind == PatchKind::Unknown) {
@@ -182,7 +550,14 @@ fn h_sections(bitmap: &[u8], offset: usize, width: usize) -> Vec<Section> {
     sections
 }
 
-fn v_sections(bitmap: &[u8], offset: usize, advance: usize, height: usize) -> Vec<Section> {
+/// Scan a vertical border, like [`h_sections`].
+fn v_sections(
+    bitmap: &[u8],
+    offset: usize,
+    advance: usize,
+    height: usize,
+    optical: bool,
+) -> Vec<Section> {
     let mut start = 0.0;
     let mut len = 0.0;
     let mut kind = PatchKind::Unknown;
@@ -191,6 +566,7 @@ fn v_sections(bitmap: &[u8], offset: usize, advance: usize, height: usize) -> Ve
         let o = offset + i * advance;
         let i_kind = match (bitmap[o], bitmap[o + 1], bitmap[o + 2]) {
             (0xFF, 0xFF, 0xFF) => PatchKind::Fixed,
+            _ if optical && is_optical_marker(bitmap, o) => PatchKind::Fixed,
             _ => PatchKind::Stretching,
         };
         if i != (height - 1) && (kind == i_kind || kind == PatchKind::Unknown) {
@@ -0,0 +1,134 @@
+use std::fmt::Display;
+
+use crate::{Axis, NinePatchDrawable, NinePatchError, PatchKind, Section};
+
+/// Channel difference between neighbouring pixels up to which a change along
+/// a stretching section is taken for a gradient rather than an edge.
+const GRADIENT_STEP: u8 = 16;
+
+/// A probable authoring mistake found by [`lint`]. Coordinates are pixels of
+/// the nine patch bitmap, including the marker border.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LintWarning {
+    /// The content changes gradually along a stretching section, which
+    /// shows as banding when stretched.
+    GradientStretch {
+        axis: Axis,
+        index: usize,
+        x: usize,
+        y: usize,
+    },
+    /// The content changes abruptly along a stretching section, so
+    /// stretching it distorts the image.
+    NonUniformStretch {
+        axis: Axis,
+        index: usize,
+        x: usize,
+        y: usize,
+    },
+    /// A marker pixel is neither opaque nor fully transparent.
+    SemiTransparentMarker { x: usize, y: usize },
+    /// A corner of the marker border is not transparent.
+    OpaqueCorner { x: usize, y: usize },
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintWarning::GradientStretch { axis, index, x, y } => {
+                write!(f, "{axis} section {index} stretches a gradient at {x},{y}")
+            }
+            LintWarning::NonUniformStretch { axis, index, x, y } => {
+                write!(f, "{axis} section {index} is not uniform at {x},{y}")
+            }
+            LintWarning::SemiTransparentMarker { x, y } => {
+                write!(f, "semi-transparent marker at {x},{y}")
+            }
+            LintWarning::OpaqueCorner { x, y } => write!(f, "corner {x},{y} is not transparent"),
+        }
+    }
+}
+
+/// Look for mistakes that still make a valid nine patch bitmap, such as
+/// stretching sections over content that does not stretch cleanly.
+///
+/// The bitmap follows the Android convention of opaque markers on a
+/// transparent border, see [`crate::clear_transparent_border`]. It is parsed
+/// like by [`NinePatchDrawable::new`] first, whose errors are returned as is,
+/// so padding that leaves no content is an error rather than a warning.
+pub fn lint(
+    bitmap: &[u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+) -> Result<Vec<LintWarning>, NinePatchError> {
+    let mut cleared = bitmap.to_vec();
+    crate::clear_transparent_border(&mut cleared, stride, width, height)?;
+    let drawable = NinePatchDrawable::new(&cleared, stride, width, height)?;
+    let alpha = |x: usize, y: usize| bitmap[y * stride + x * 4 + 3];
+    let mut warnings = vec![];
+
+    for (x, y) in [
+        (0, 0),
+        (width - 1, 0),
+        (0, height - 1),
+        (width - 1, height - 1),
+    ] {
+        if alpha(x, y) != 0 {
+            warnings.push(LintWarning::OpaqueCorner { x, y });
+        }
+    }
+    let border = (1..width - 1)
+        .flat_map(|x| [(x, 0), (x, height - 1)])
+        .chain((1..height - 1).flat_map(|y| [(0, y), (width - 1, y)]));
+    for (x, y) in border {
+        if !matches!(alpha(x, y), 0 | 0xFF) {
+            warnings.push(LintWarning::SemiTransparentMarker { x, y });
+        }
+    }
+
+    let pixel = |x: usize, y: usize| &bitmap[y * stride + x * 4..y * stride + x * 4 + 4];
+    let step = |a: &[u8], b: &[u8]| a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max();
+    for (index, range) in stretching(&drawable.h_sections) {
+        let changes = range
+            .skip(1)
+            .flat_map(|x| (1..height - 1).map(move |y| (x, y, step(pixel(x, y), pixel(x - 1, y)))));
+        warnings.extend(uniformity(Axis::Horizontal, index, changes));
+    }
+    for (index, range) in stretching(&drawable.v_sections) {
+        let changes = range
+            .skip(1)
+            .flat_map(|y| (1..width - 1).map(move |x| (x, y, step(pixel(x, y), pixel(x, y - 1)))));
+        warnings.extend(uniformity(Axis::Vertical, index, changes));
+    }
+    Ok(warnings)
+}
+
+/// The bitmap pixels of each stretching section, with the section index.
+fn stretching(sections: &[Section]) -> impl Iterator<Item = (usize, std::ops::Range<usize>)> + '_ {
+    sections
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.kind != PatchKind::Fixed)
+        .map(|(i, s)| (i, s.start as usize + 1..(s.start + s.len) as usize + 1))
+}
+
+/// A warning for the first abrupt change along a section, or else for the
+/// first gradual one.
+fn uniformity(
+    axis: Axis,
+    index: usize,
+    changes: impl Iterator<Item = (usize, usize, Option<u8>)>,
+) -> Option<LintWarning> {
+    let mut gradient = None;
+    for (x, y, step) in changes {
+        match step.unwrap_or(0) {
+            0 => {}
+            1..=GRADIENT_STEP => {
+                gradient.get_or_insert(LintWarning::GradientStretch { axis, index, x, y });
+            }
+            _ => return Some(LintWarning::NonUniformStretch { axis, index, x, y }),
+        }
+    }
+    gradient
+}
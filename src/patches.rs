@@ -0,0 +1,245 @@
+use std::iter::FusedIterator;
+
+use crate::{
+    Axis, NinePatchDrawable, NinePatchError, Patch, PatchKind, RectF, ScaleOptions, Section,
+    StretchFallback, StretchPolicy,
+};
+
+impl NinePatchDrawable {
+    /// Iterate over the patches of [`NinePatchDrawable::scale_to`] in row
+    /// major order without allocating.
+    ///
+    /// The section sums are computed once when the iterator is created, each
+    /// patch then takes constant time. Each patch starts exactly where the
+    /// previous one in its row ends, and each row where the previous row
+    /// ends, the same edges as [`crate::NinePatchLayout::column_edges`] and
+    /// [`crate::NinePatchLayout::row_edges`].
+    pub fn patches(&self, width: usize, height: usize) -> Patches<'_> {
+        assert!(width >= self.width && height >= self.height);
+
+        self.patches_with(width, height, &ScaleOptions::default())
+            .expect("default scale options never fail")
+    }
+
+    /// Like [`NinePatchDrawable::patches`], with explicit [`ScaleOptions`].
+    ///
+    /// With a [`ScaleOptions::distribution`] policy the weights of each axis
+    /// are collected into a vector once, the only allocation.
+    pub fn patches_with(
+        &self,
+        width: usize,
+        height: usize,
+        options: &ScaleOptions,
+    ) -> Result<Patches<'_>, NinePatchError> {
+        let h_scale = AxisScale::new(
+            Axis::Horizontal,
+            &self.h_sections,
+            self.width,
+            width,
+            options,
+        )?;
+        let v_scale = AxisScale::new(
+            Axis::Vertical,
+            &self.v_sections,
+            self.height,
+            height,
+            options,
+        )?;
+        Ok(Patches {
+            h_sections: &self.h_sections,
+            v_sections: &self.v_sections,
+            left: h_scale.origin,
+            top: v_scale.origin,
+            h_scale,
+            v_scale,
+            row: 0,
+            column: 0,
+        })
+    }
+
+    /// Like [`NinePatchDrawable::scale_to_with`], reusing the allocation of
+    /// `patches`, which is cleared first.
+    pub fn scale_into(
+        &self,
+        width: usize,
+        height: usize,
+        options: &ScaleOptions,
+        patches: &mut Vec<Patch>,
+    ) -> Result<(), NinePatchError> {
+        patches.clear();
+        patches.extend(self.patches_with(width, height, options)?);
+        Ok(())
+    }
+}
+
+/// Iterator over the patches of a scaled drawable, created by
+/// [`NinePatchDrawable::patches`].
+pub struct Patches<'a> {
+    h_sections: &'a [Section],
+    v_sections: &'a [Section],
+    h_scale: AxisScale,
+    v_scale: AxisScale,
+    row: usize,
+    column: usize,
+    left: f64,
+    top: f64,
+}
+
+impl Iterator for Patches<'_> {
+    type Item = Patch;
+
+    fn next(&mut self) -> Option<Patch> {
+        let v = self.v_sections.get(self.row)?;
+        let h = self.h_sections.get(self.column)?;
+        let right = self.left + self.h_scale.len(self.h_sections, self.column);
+        let bottom = self.top + self.v_scale.len(self.v_sections, self.row);
+        let patch = Patch {
+            source: RectF {
+                left: h.start + 1.0,
+                top: v.start + 1.0,
+                right: h.start + 1.0 + h.len,
+                bottom: v.start + 1.0 + v.len,
+            },
+            target: RectF {
+                left: self.left as f32,
+                top: self.top as f32,
+                right: right as f32,
+                bottom: bottom as f32,
+            },
+            h_kind: h.kind,
+            v_kind: v.kind,
+        };
+        self.column += 1;
+        self.left = right;
+        if self.column == self.h_sections.len() {
+            self.column = 0;
+            self.row += 1;
+            self.left = self.h_scale.origin;
+            self.top = bottom;
+        }
+        Some(patch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.h_sections.is_empty() {
+            0
+        } else {
+            (self.v_sections.len() - self.row.min(self.v_sections.len())) * self.h_sections.len()
+                - self.column
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Patches<'_> {}
+
+impl FusedIterator for Patches<'_> {}
+
+/// How the sections of one axis map onto the target length.
+///
+/// Computed in `f64` whatever the output [`crate::Scalar`], so that large
+/// targets keep their precision until the edges are converted.
+pub(crate) struct AxisScale {
+    /// Target position of the first section.
+    pub(crate) origin: f64,
+    /// Total source length of the stretching sections.
+    stretching: f64,
+    /// Target length shared by the stretching sections.
+    extra: f64,
+    /// Scale factor of the fixed sections.
+    density: f64,
+    /// Whether fixed sections stretch as well.
+    stretch_all: bool,
+    /// The share of each section in the growth beyond the source length,
+    /// `None` for proportional.
+    shares: Option<Vec<f64>>,
+}
+
+impl AxisScale {
+    pub(crate) fn new(
+        axis: Axis,
+        sections: &[Section],
+        source_len: usize,
+        target_len: usize,
+        options: &ScaleOptions,
+    ) -> Result<AxisScale, NinePatchError> {
+        if !(options.density > 0.0 && options.density.is_finite()) {
+            return Err(NinePatchError::InvalidDensity);
+        }
+        let density = f64::from(options.density);
+        let stretching: f64 = sections
+            .iter()
+            .filter(|s| s.kind != PatchKind::Fixed)
+            .map(|s| f64::from(s.len))
+            .sum();
+        let fixed = (source_len as f64 - 2.0 - stretching) * density;
+        let extra = target_len as f64 - 2.0 - fixed;
+        if extra < 0.0 && !(stretching == 0.0 && options.fallback == StretchFallback::StretchAll) {
+            return Err(NinePatchError::TargetTooSmall { axis });
+        }
+        if stretching > 0.0 {
+            let growth = extra - stretching * density;
+            let shares = options
+                .distribution
+                .as_ref()
+                .filter(|_| growth > 0.0)
+                .and_then(|policy| shares(policy.as_ref(), axis, sections));
+            return Ok(AxisScale {
+                origin: 1.0,
+                stretching,
+                extra,
+                density,
+                stretch_all: false,
+                shares,
+            });
+        }
+        match options.fallback {
+            StretchFallback::StretchAll => Ok(AxisScale {
+                origin: 1.0,
+                stretching: sections.iter().map(|s| f64::from(s.len)).sum(),
+                extra: target_len as f64 - 2.0,
+                density,
+                stretch_all: true,
+                shares: None,
+            }),
+            StretchFallback::Center => Ok(AxisScale {
+                origin: 1.0 + extra / 2.0,
+                stretching,
+                extra: 0.0,
+                density,
+                stretch_all: false,
+                shares: None,
+            }),
+            StretchFallback::Error => Err(NinePatchError::NoStretchSection { axis }),
+        }
+    }
+
+    /// The target length of `sections[index]`.
+    pub(crate) fn len(&self, sections: &[Section], index: usize) -> f64 {
+        let section = &sections[index];
+        let len = f64::from(section.len);
+        if section.kind == PatchKind::Fixed && !self.stretch_all {
+            len * self.density
+        } else if let Some(shares) = &self.shares {
+            len * self.density + shares[index] * (self.extra - self.stretching * self.density)
+        } else {
+            (len / self.stretching) * self.extra
+        }
+    }
+}
+
+/// The share of each stretching section of `policy`, `None` if all weights
+/// are zero.
+fn shares(policy: &dyn StretchPolicy, axis: Axis, sections: &[Section]) -> Option<Vec<f64>> {
+    let weights: Vec<f64> = (0..sections.len())
+        .map(|i| match sections[i].kind {
+            PatchKind::Fixed => 0.0,
+            _ => match policy.weight(axis, sections, i) {
+                weight if weight.is_finite() => f64::from(weight.max(0.0)),
+                _ => 0.0,
+            },
+        })
+        .collect();
+    let total: f64 = weights.iter().sum();
+    (total > 0.0).then(|| weights.iter().map(|weight| weight / total).collect())
+}
@@ -0,0 +1,146 @@
+use crate::transform::flip_horizontally;
+use crate::{
+    ColorMatrix, NinePatchDrawable, NinePatchError, PatchKind, PixelFormat, RectF, ScaleOptions,
+    Tint,
+};
+
+/// Options for [`render`].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RenderOptions {
+    pub scale: ScaleOptions,
+    /// The channel order of the bitmap and of the output.
+    pub format: PixelFormat,
+    /// Color matrix applied to every drawn pixel, before the tint.
+    pub color_matrix: Option<ColorMatrix>,
+    /// Tint applied to every drawn pixel.
+    pub tint: Option<Tint>,
+    /// Draw mirrored horizontally, for drawables that are automatically
+    /// mirrored in right-to-left layouts.
+    pub rtl: bool,
+}
+
+impl RenderOptions {
+    /// Apply the color matrix and the tint to a pixel in [`RenderOptions::format`].
+    pub fn filter(&self, pixel: [u8; 4]) -> [u8; 4] {
+        if self.color_matrix.is_none() && self.tint.is_none() {
+            return pixel;
+        }
+        let mut rgba = self.format.swap_rgba(pixel);
+        if let Some(matrix) = &self.color_matrix {
+            rgba = matrix.apply(rgba);
+        }
+        if let Some(tint) = &self.tint {
+            rgba = tint.apply(rgba);
+        }
+        self.format.swap_rgba(rgba)
+    }
+}
+
+/// Draw the nine patch `bitmap` scaled to `width` x `height` pixels.
+///
+/// `bitmap` is the whole nine patch bitmap including the marker border, in
+/// the same layout as the one passed to [`NinePatchDrawable::new`]. The
+/// result has the same pixel format without the marker border, with a stride
+/// of `width * 4`. Pixels are sampled with the nearest neighbour, areas not
+/// covered by any patch are left transparent.
+///
+/// The color filters of the options are applied to the drawn pixels, the
+/// marker border never takes part in the output.
+pub fn render(
+    bitmap: &[u8],
+    stride: usize,
+    drawable: &NinePatchDrawable,
+    width: usize,
+    height: usize,
+    options: &RenderOptions,
+) -> Result<Vec<u8>, NinePatchError> {
+    if bitmap.len() != stride * drawable.height || stride < drawable.width * 4 {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    let patches = drawable.scale_to_with(width + 2, height + 2, &options.scale)?;
+    let mut pixels = vec![0; width * height * 4];
+    for patch in &patches {
+        let (x_range, y_range) = pixel_range(&patch.target, width, height);
+        for y in y_range {
+            let sy = sample(
+                (patch.source.top, patch.source.bottom),
+                (patch.target.top, patch.target.bottom),
+                patch.v_kind,
+                y,
+                options.scale.density,
+            );
+            for x in x_range.clone() {
+                let sx = sample(
+                    (patch.source.left, patch.source.right),
+                    (patch.target.left, patch.target.right),
+                    patch.h_kind,
+                    x,
+                    options.scale.density,
+                );
+                let o = sy * stride + sx * 4;
+                let p = (y * width + x) * 4;
+                let pixel = options.filter(bitmap[o..o + 4].try_into().unwrap());
+                pixels[p..p + 4].copy_from_slice(&pixel);
+            }
+        }
+    }
+    if options.rtl {
+        flip_horizontally(&mut pixels, width);
+    }
+    Ok(pixels)
+}
+
+/// Apply the color filters of `options` to the content of a nine patch
+/// bitmap in place, leaving the marker border untouched so the bitmap can
+/// still be parsed by [`NinePatchDrawable::new`].
+pub fn filter_content(
+    bitmap: &mut [u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+    options: &RenderOptions,
+) -> Result<(), NinePatchError> {
+    if bitmap.len() != stride * height || stride < width * 4 || width < 3 || height < 3 {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let o = y * stride + x * 4;
+            let pixel = options.filter(bitmap[o..o + 4].try_into().unwrap());
+            bitmap[o..o + 4].copy_from_slice(&pixel);
+        }
+    }
+    Ok(())
+}
+
+/// The output pixels whose centers are inside `target`. Targets are in the
+/// coordinates of a bitmap with a marker border, the output has none.
+pub(crate) fn pixel_range(
+    target: &RectF,
+    width: usize,
+    height: usize,
+) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+    let first = |edge: f32| (edge - 1.5).ceil().max(0.0) as usize;
+    (
+        first(target.left)..first(target.right).min(width),
+        first(target.top)..first(target.bottom).min(height),
+    )
+}
+
+/// Map the center of output pixel `i` back to a source pixel.
+fn sample(
+    (s0, s1): (f32, f32),
+    (t0, t1): (f32, f32),
+    kind: PatchKind,
+    i: usize,
+    density: f32,
+) -> usize {
+    let offset = i as f32 + 1.5 - t0;
+    let s = match kind {
+        PatchKind::Tiling => s0 + (offset / density) % (s1 - s0),
+        _ => s0 + offset / (t1 - t0) * (s1 - s0),
+    };
+    // Sections narrower than a pixel still sample the pixel they start in.
+    let last = (s1.ceil() as usize).max(s0 as usize + 1) - 1;
+    (s.floor() as usize).clamp(s0 as usize, last)
+}
@@ -0,0 +1,93 @@
+use std::fmt::Debug;
+
+use crate::{Patch, Rect, Section};
+
+/// A coordinate type for [`Rect`], [`Section`] and [`Patch`].
+///
+/// Layouts are computed in `f64` and converted edge by edge. Floating point
+/// scalars keep fractional positions, integer scalars round every edge to the
+/// nearest pixel, so adjacent patches still touch and the stretching sections
+/// still exactly fill the target.
+pub trait Scalar: Copy + PartialOrd + Debug + Default {
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl Scalar for f32 {
+    fn from_f64(value: f64) -> f32 {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(value: f64) -> f64 {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Scalar for i32 {
+    /// Rounds to the nearest integer, saturating at the bounds of `i32`.
+    fn from_f64(value: f64) -> i32 {
+        value.round() as i32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl Scalar for u32 {
+    /// Rounds to the nearest integer, saturating at the bounds of `u32`.
+    fn from_f64(value: f64) -> u32 {
+        value.round() as u32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl<T: Scalar> Rect<T> {
+    /// Convert to another scalar type, rounding each edge for integers.
+    pub fn cast<U: Scalar>(&self) -> Rect<U> {
+        Rect {
+            left: U::from_f64(self.left.to_f64()),
+            top: U::from_f64(self.top.to_f64()),
+            right: U::from_f64(self.right.to_f64()),
+            bottom: U::from_f64(self.bottom.to_f64()),
+        }
+    }
+}
+
+impl<T: Scalar> Section<T> {
+    /// Convert to another scalar type, rounding both ends for integers.
+    pub fn cast<U: Scalar>(&self) -> Section<U> {
+        let start = U::from_f64(self.start.to_f64());
+        let end = U::from_f64(self.start.to_f64() + self.len.to_f64());
+        Section {
+            start,
+            len: U::from_f64(end.to_f64() - start.to_f64()),
+            kind: self.kind,
+        }
+    }
+}
+
+impl<T: Scalar> Patch<T> {
+    /// Convert to another scalar type, rounding each edge for integers.
+    pub fn cast<U: Scalar>(&self) -> Patch<U> {
+        Patch {
+            source: self.source.cast(),
+            target: self.target.cast(),
+            h_kind: self.h_kind,
+            v_kind: self.v_kind,
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{Axis, NinePatchDrawable, NinePatchError, Patch, PatchKind, ScaleOptions, Section};
+
+/// The same nine patch authored at several densities, such as the `@1x`,
+/// `@2x` and `@3x` variants of an asset or its `drawable-hdpi` and
+/// `drawable-xhdpi` versions.
+///
+/// Densities are scale factors relative to the baseline, so `mdpi` and `@1x`
+/// are `1.0`, `xhdpi` and `@2x` are `2.0`.
+#[derive(Debug, Default)]
+pub struct NinePatchSet {
+    /// Sorted by density.
+    drawables: Vec<(f32, NinePatchDrawable)>,
+}
+
+impl NinePatchSet {
+    pub fn new() -> NinePatchSet {
+        NinePatchSet::default()
+    }
+
+    /// Add a drawable authored for `density`, replacing any drawable already
+    /// present for it.
+    ///
+    /// The drawable must have the same section structure as the others in the
+    /// set: the same section kinds in the same order, with fixed sections
+    /// whose lengths match after scaling by the density ratio.
+    pub fn insert(
+        &mut self,
+        density: f32,
+        drawable: NinePatchDrawable,
+    ) -> Result<(), NinePatchError> {
+        if !(density > 0.0 && density.is_finite()) {
+            return Err(NinePatchError::InvalidDensity);
+        }
+        if let Some((other_density, other)) = self.drawables.iter().find(|(d, _)| *d != density) {
+            let ratio = density / other_density;
+            check_sections(
+                Axis::Horizontal,
+                &other.h_sections,
+                &drawable.h_sections,
+                ratio,
+            )?;
+            check_sections(
+                Axis::Vertical,
+                &other.v_sections,
+                &drawable.v_sections,
+                ratio,
+            )?;
+        }
+        match self
+            .drawables
+            .binary_search_by(|(d, _)| d.total_cmp(&density))
+        {
+            Ok(i) => self.drawables[i].1 = drawable,
+            Err(i) => self.drawables.insert(i, (density, drawable)),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, density: f32) -> Option<&NinePatchDrawable> {
+        self.drawables
+            .iter()
+            .find(|(d, _)| *d == density)
+            .map(|(_, drawable)| drawable)
+    }
+
+    /// Iterate over the drawables and their densities, from the lowest
+    /// density to the highest.
+    pub fn iter(&self) -> impl Iterator<Item = (f32, &NinePatchDrawable)> {
+        self.drawables.iter().map(|(d, drawable)| (*d, drawable))
+    }
+
+    pub fn len(&self) -> usize {
+        self.drawables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drawables.is_empty()
+    }
+
+    /// Pick the drawable to draw at `width` x `height` on a device with the
+    /// given density.
+    ///
+    /// Like Android, an exact match is preferred, then the closest higher
+    /// density, which only needs to be scaled down, then the closest lower
+    /// density. Drawables whose fixed sections do not fit in the target size
+    /// are skipped.
+    pub fn select(
+        &self,
+        density: f32,
+        width: usize,
+        height: usize,
+    ) -> Option<(f32, &NinePatchDrawable)> {
+        let higher = self.drawables.iter().filter(|(d, _)| *d >= density);
+        let lower = self.drawables.iter().rev().filter(|(d, _)| *d < density);
+        higher
+            .chain(lower)
+            .find(|(d, drawable)| {
+                drawable
+                    .scale_to_with(width, height, &options(*d, density))
+                    .is_ok()
+            })
+            .map(|(d, drawable)| (*d, drawable))
+    }
+
+    /// Scale the drawable chosen by [`NinePatchSet::select`] to `width` x
+    /// `height` device pixels.
+    pub fn scale_to(&self, density: f32, width: usize, height: usize) -> Option<Vec<Patch>> {
+        let (source_density, drawable) = self.select(density, width, height)?;
+        drawable
+            .scale_to_with(width, height, &options(source_density, density))
+            .ok()
+    }
+}
+
+/// Parse the density of an Android resource qualifier such as `xhdpi` or
+/// `drawable-xhdpi`, or of an `@2x` style file name suffix.
+pub fn parse_density(qualifier: &str) -> Option<f32> {
+    if let Some((_, suffix)) = qualifier.rsplit_once('@') {
+        let (scale, _) = suffix.split_once('x')?;
+        return scale
+            .parse()
+            .ok()
+            .filter(|d: &f32| *d > 0.0 && d.is_finite());
+    }
+    qualifier.split('-').find_map(|q| match q {
+        "ldpi" => Some(0.75),
+        "mdpi" => Some(1.0),
+        "tvdpi" => Some(1.33),
+        "hdpi" => Some(1.5),
+        "xhdpi" => Some(2.0),
+        "xxhdpi" => Some(3.0),
+        "xxxhdpi" => Some(4.0),
+        _ => None,
+    })
+}
+
+fn options(source_density: f32, target_density: f32) -> ScaleOptions {
+    ScaleOptions {
+        density: target_density / source_density,
+        ..ScaleOptions::default()
+    }
+}
+
+/// Check that the sections of one axis of two drawables correspond, with
+/// fixed sections whose lengths match after scaling by `ratio`.
+pub(crate) fn check_sections(
+    axis: Axis,
+    a: &[Section],
+    b: &[Section],
+    ratio: f32,
+) -> Result<(), NinePatchError> {
+    if a.len() != b.len() {
+        return Err(NinePatchError::MismatchedSections { axis });
+    }
+    for (a, b) in a.iter().zip(b) {
+        if a.kind != b.kind {
+            return Err(NinePatchError::MismatchedSections { axis });
+        }
+        // Allow a pixel of rounding in either direction.
+        if a.kind == PatchKind::Fixed && (a.len * ratio - b.len).abs() > 1.0 {
+            return Err(NinePatchError::MismatchedSections { axis });
+        }
+    }
+    Ok(())
+}
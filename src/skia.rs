@@ -0,0 +1,109 @@
+use tiny_skia::{
+    IntRect, Paint, Pattern, Pixmap, PixmapMut, PixmapPaint, Rect, SpreadMode, Transform,
+};
+
+use crate::{NinePatchDrawable, NinePatchError, NinePatchLayout, PatchKind, ScaleOptions};
+
+/// Draw the nine patch `bitmap` into `dest` with tiny-skia.
+///
+/// `bitmap` is the whole nine patch bitmap including the marker border. The
+/// layout is computed for `dest` rounded to whole pixels and then mapped onto
+/// `dest` exactly, so fractional destinations are drawn anti-aliased along
+/// their outer edges. Edges between patches are snapped to whole pixels so
+/// adjacent patches never leave a seam.
+///
+/// Each patch is drawn from a copy of its source rectangle, which keeps
+/// bilinear and bicubic filtering from sampling neighbouring patches or the
+/// markers.
+pub fn draw_nine_patch(
+    pixmap: &mut PixmapMut,
+    bitmap: &Pixmap,
+    drawable: &NinePatchDrawable,
+    dest: Rect,
+    paint: &PixmapPaint,
+) -> Result<(), NinePatchError> {
+    if bitmap.width() as usize != drawable.width || bitmap.height() as usize != drawable.height {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    let width = dest.width().round() as usize;
+    let height = dest.height().round() as usize;
+    let options = ScaleOptions::default();
+    let layout = NinePatchLayout::new(drawable, width + 2, height + 2, &options)?;
+    let columns = dest_edges(layout.column_edges(), dest.left(), dest.right(), width);
+    let rows = dest_edges(layout.row_edges(), dest.top(), dest.bottom(), height);
+
+    for (row, &v_kind) in layout.row_kinds().iter().enumerate() {
+        for (column, &h_kind) in layout.column_kinds().iter().enumerate() {
+            let patch = layout.patch(column, row).expect("index is within the grid");
+            let Some(target) = Rect::from_ltrb(
+                columns[column],
+                rows[row],
+                columns[column + 1],
+                rows[row + 1],
+            ) else {
+                continue;
+            };
+            // Sections narrower than a pixel still draw the pixel they start in.
+            let (left, top) = (patch.source.left.floor(), patch.source.top.floor());
+            let source = IntRect::from_ltrb(
+                left as i32,
+                top as i32,
+                patch.source.right.ceil().max(left + 1.0) as i32,
+                patch.source.bottom.ceil().max(top + 1.0) as i32,
+            )
+            .and_then(|rect| bitmap.clone_rect(rect))
+            .ok_or(NinePatchError::InvalidBitmap)?;
+            let scale = |kind: PatchKind, target: f32, source: u32| match kind {
+                PatchKind::Tiling => options.density,
+                _ => target / source as f32,
+            };
+            let transform = Transform::from_row(
+                scale(h_kind, target.width(), source.width()),
+                0.0,
+                0.0,
+                scale(v_kind, target.height(), source.height()),
+                target.left(),
+                target.top(),
+            );
+            let spread_mode = if h_kind == PatchKind::Tiling || v_kind == PatchKind::Tiling {
+                SpreadMode::Repeat
+            } else {
+                SpreadMode::Pad
+            };
+            let paint = Paint {
+                shader: Pattern::new(
+                    source.as_ref(),
+                    spread_mode,
+                    paint.quality,
+                    paint.opacity,
+                    transform,
+                ),
+                blend_mode: paint.blend_mode,
+                anti_alias: true,
+                force_hq_pipeline: false,
+            };
+            pixmap.fill_rect(target, &paint, Transform::identity(), None);
+        }
+    }
+    Ok(())
+}
+
+/// Map layout edges, in a frame where the content spans `1..len + 1`, onto
+/// `start..end`, rounding all but the outer edges.
+fn dest_edges(edges: &[f32], start: f32, end: f32, len: usize) -> Vec<f32> {
+    let scale = if len == 0 {
+        0.0
+    } else {
+        (end - start) / len as f32
+    };
+    let last = edges.len().saturating_sub(1);
+    edges
+        .iter()
+        .enumerate()
+        .map(|(i, edge)| match i {
+            0 => start,
+            i if i == last => end,
+            _ => (start + (edge - 1.0) * scale).round(),
+        })
+        .collect()
+}
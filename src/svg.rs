@@ -0,0 +1,115 @@
+use std::fmt::Write;
+
+use crate::{NinePatchDrawable, NinePatchError, PatchKind, ScaleOptions};
+
+/// Render the drawable scaled to `width` x `height` as an SVG document.
+///
+/// `image` is the encoded nine patch bitmap including the marker border,
+/// such as the contents of a `.9.png` file, and `mime_type` its media type.
+/// It is embedded once as a data URI. Each patch is a nested `<svg>` whose
+/// view box is the source rectangle, which clips and scales the image, and
+/// tiling patches fill their target with a `<pattern>` instead.
+pub fn to_svg(
+    drawable: &NinePatchDrawable,
+    image: &[u8],
+    mime_type: &str,
+    width: usize,
+    height: usize,
+    options: &ScaleOptions,
+) -> Result<String, NinePatchError> {
+    let patches = drawable.scale_to_with(width + 2, height + 2, options)?;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+         width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+    )
+    .unwrap();
+    svg.push_str("<defs>\n");
+    writeln!(
+        svg,
+        "<image id=\"nine-patch\" width=\"{}\" height=\"{}\" xlink:href=\"data:{mime_type};base64,{}\"/>",
+        drawable.width,
+        drawable.height,
+        base64(image)
+    )
+    .unwrap();
+    for (i, patch) in patches.iter().enumerate() {
+        if patch.h_kind != PatchKind::Tiling && patch.v_kind != PatchKind::Tiling {
+            continue;
+        }
+        let (s, t) = (&patch.source, &patch.target);
+        let tile_width = match patch.h_kind {
+            PatchKind::Tiling => (s.right - s.left) * options.density,
+            _ => t.right - t.left,
+        };
+        let tile_height = match patch.v_kind {
+            PatchKind::Tiling => (s.bottom - s.top) * options.density,
+            _ => t.bottom - t.top,
+        };
+        writeln!(
+            svg,
+            "<pattern id=\"tile-{i}\" patternUnits=\"userSpaceOnUse\" x=\"{}\" y=\"{}\" \
+             width=\"{tile_width}\" height=\"{tile_height}\" viewBox=\"{} {} {} {}\" \
+             preserveAspectRatio=\"none\"><use xlink:href=\"#nine-patch\"/></pattern>",
+            t.left - 1.0,
+            t.top - 1.0,
+            s.left,
+            s.top,
+            s.right - s.left,
+            s.bottom - s.top
+        )
+        .unwrap();
+    }
+    svg.push_str("</defs>\n");
+    for (i, patch) in patches.iter().enumerate() {
+        let (s, t) = (&patch.source, &patch.target);
+        let (x, y, w, h) = (
+            t.left - 1.0,
+            t.top - 1.0,
+            t.right - t.left,
+            t.bottom - t.top,
+        );
+        if patch.h_kind == PatchKind::Tiling || patch.v_kind == PatchKind::Tiling {
+            writeln!(
+                svg,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" fill=\"url(#tile-{i})\"/>"
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                svg,
+                "<svg x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" viewBox=\"{} {} {} {}\" \
+                 preserveAspectRatio=\"none\"><use xlink:href=\"#nine-patch\"/></svg>",
+                s.left,
+                s.top,
+                s.right - s.left,
+                s.bottom - s.top
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -1,6 +1,66 @@
-use crate::{NinePatchDrawable, Patch, PatchKind::*, RectF};
+use crate::{
+    Axis, Insets, LayoutCache, LintWarning, NinePatchBuilder, NinePatchDrawable, NinePatchError,
+    NinePatchLayout, NinePatchSet, NinePatchTransition, Patch, PatchKind::*, Rect, RectF,
+    RenderOptions, ScaleOptions, Section, StretchFallback,
+};
 use pretty_assertions::assert_eq;
 
+#[rustfmt::skip]
+const NINE_PATCH: [u8; 63] = [
+    0, 9, 0, 0, 0, 9, 0, 0, 0,
+    9, 6, 1, 1, 1, 6, 0, 0, 0,
+    0, 1, 1, 1, 1, 1, 9, 0, 0,
+    0, 1, 1, 1, 1, 1, 9, 0, 0,
+    0, 1, 1, 1, 1, 1, 9, 0, 0,
+    9, 6, 1, 1, 1, 6, 0, 0, 0,
+    0, 0, 9, 9, 9, 0, 0, 0, 0,
+];
+
+#[rustfmt::skip]
+const TWENTY_FIVE_PATCH: [u8; 63] = [
+    0, 9, 0, 9, 0, 9, 0, 0, 0,
+    9, 6, 1, 1, 1, 6, 0, 0, 0,
+    0, 1, 1, 1, 1, 1, 9, 0, 0,
+    9, 1, 1, 1, 1, 1, 9, 0, 0,
+    0, 1, 1, 1, 1, 1, 9, 0, 0,
+    9, 6, 1, 1, 1, 6, 0, 0, 0,
+    0, 0, 9, 9, 9, 0, 0, 0, 0,
+];
+
+/// Expand a 9 x 7 monochrome fixture into an RGBA bitmap with a stride of 36.
+fn bitmap_from_monochrome(monochrome: &[u8]) -> Vec<u8> {
+    let mut bitmap = vec![];
+    for &color in monochrome {
+        match color {
+            0 => bitmap.extend_from_slice(&[0, 0, 0, 0]),
+            1 => bitmap.extend_from_slice(&[1, 1, 1, 0]),
+            2 => bitmap.extend_from_slice(&[0xFF, 0, 0, 0]),
+            6 => bitmap.extend_from_slice(&[0x80, 0x80, 0x80, 0]),
+            9 => bitmap.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0]),
+            _ => unreachable!(),
+        }
+    }
+    bitmap
+}
+
+/// Like [`bitmap_from_monochrome`], with the border written like Android
+/// tools do: opaque markers on transparent black, with transparent corners.
+fn android_bitmap_from_monochrome(monochrome: &[u8], width: usize) -> Vec<u8> {
+    let height = monochrome.len() / width;
+    let mut bitmap = bitmap_from_monochrome(monochrome);
+    for (i, pixel) in bitmap.chunks_mut(4).enumerate() {
+        let (x, y) = (i % width, i / width);
+        let (h_border, v_border) = (x == 0 || x == width - 1, y == 0 || y == height - 1);
+        match pixel {
+            _ if h_border && v_border => pixel.copy_from_slice(&[0, 0, 0, 0]),
+            [0xFF, 0xFF, 0xFF, _] if h_border || v_border => pixel.copy_from_slice(&[0, 0, 0, 0]),
+            _ if h_border || v_border => pixel[3] = 0xFF,
+            _ => {}
+        }
+    }
+    bitmap
+}
+
 #[test]
 fn nine_slice() {
     #[rustfmt::skip]
@@ -1168,3 +1228,1677 @@ fn twenty_five_patch() {
         ]
     );
 }
+
+#[test]
+fn builder_from_insets() {
+    let parsed = NinePatchDrawable::new(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7).unwrap();
+    let built = NinePatchBuilder::new(5, 5)
+        .insets(Insets {
+            left: 1.0,
+            top: 1.0,
+            right: 1.0,
+            bottom: 1.0,
+        })
+        .padding(Insets {
+            left: 1.0,
+            top: 1.0,
+            right: 1.0,
+            bottom: 1.0,
+        })
+        .build()
+        .unwrap();
+    assert_eq!(parsed, built);
+}
+
+#[test]
+fn builder_from_stretch_ranges() {
+    let parsed =
+        NinePatchDrawable::new(&bitmap_from_monochrome(&TWENTY_FIVE_PATCH), 36, 7, 7).unwrap();
+    let built = NinePatchBuilder::new(5, 5)
+        .h_stretch(3.0..4.0)
+        .h_stretch(1.0..2.0)
+        .v_stretch(1.0..2.0)
+        .v_stretch(3.0..4.0)
+        .padding(Insets {
+            left: 1.0,
+            top: 1.0,
+            right: 1.0,
+            bottom: 1.0,
+        })
+        .build()
+        .unwrap();
+    assert_eq!(parsed.h_sections, built.h_sections);
+    assert_eq!(parsed.v_sections, built.v_sections);
+    assert_eq!(parsed.scale_to(48, 48), built.scale_to(48, 48));
+}
+
+#[test]
+fn builder_rejects_invalid_ranges() {
+    assert_eq!(
+        NinePatchBuilder::new(10, 10)
+            .h_stretch(4.0..8.0)
+            .h_stretch(2.0..5.0)
+            .build(),
+        Err(NinePatchError::SectionOverlap {
+            axis: Axis::Horizontal,
+            index: 0
+        })
+    );
+    assert_eq!(
+        NinePatchBuilder::new(10, 10).v_stretch(8.0..12.0).build(),
+        Err(NinePatchError::SectionOutOfBounds {
+            axis: Axis::Vertical,
+            index: 0
+        })
+    );
+    assert_eq!(
+        NinePatchBuilder::new(10, 10).h_stretch(3.0..3.0).build(),
+        Err(NinePatchError::EmptySection {
+            axis: Axis::Horizontal,
+            index: 0
+        })
+    );
+    assert_eq!(
+        NinePatchBuilder::new(10, 10)
+            .padding(Insets {
+                left: 6.0,
+                right: 6.0,
+                ..Insets::default()
+            })
+            .build(),
+        Err(NinePatchError::InvalidMargin)
+    );
+}
+
+#[test]
+fn validate_sections() {
+    let drawable = NinePatchDrawable::new(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7).unwrap();
+    assert_eq!(drawable.validate(), Ok(()));
+
+    let section = |start, len, kind| Section { start, len, kind };
+    let padding = Insets::default();
+    assert_eq!(
+        NinePatchDrawable::from_sections(
+            7,
+            7,
+            vec![section(0.0, 2.0, Fixed), section(3.0, 2.0, Stretching)],
+            vec![section(0.0, 5.0, Stretching)],
+            padding,
+        ),
+        Err(NinePatchError::SectionGap {
+            axis: Axis::Horizontal,
+            index: 1
+        })
+    );
+    assert_eq!(
+        NinePatchDrawable::from_sections(
+            7,
+            7,
+            vec![section(0.0, 5.0, Stretching)],
+            vec![section(0.0, 3.0, Fixed), section(2.0, 3.0, Stretching)],
+            padding,
+        ),
+        Err(NinePatchError::SectionOverlap {
+            axis: Axis::Vertical,
+            index: 1
+        })
+    );
+    assert_eq!(
+        NinePatchDrawable::from_sections(
+            7,
+            7,
+            vec![section(0.0, 0.0, Fixed), section(0.0, 5.0, Stretching)],
+            vec![section(0.0, 5.0, Stretching)],
+            padding,
+        ),
+        Err(NinePatchError::EmptySection {
+            axis: Axis::Horizontal,
+            index: 0
+        })
+    );
+    assert_eq!(
+        NinePatchDrawable::from_sections(
+            7,
+            7,
+            vec![section(0.0, 5.0, Stretching)],
+            vec![section(0.0, 4.0, Stretching)],
+            padding,
+        ),
+        Err(NinePatchError::IncompleteCoverage {
+            axis: Axis::Vertical
+        })
+    );
+    assert_eq!(
+        NinePatchDrawable::from_sections(
+            7,
+            7,
+            vec![section(0.0, 6.0, Stretching)],
+            vec![section(0.0, 5.0, Stretching)],
+            padding,
+        ),
+        Err(NinePatchError::SectionOutOfBounds {
+            axis: Axis::Horizontal,
+            index: 0
+        })
+    );
+    assert_eq!(
+        NinePatchDrawable::from_sections(
+            7,
+            7,
+            vec![section(0.0, 5.0, Stretching)],
+            vec![section(0.0, 5.0, Stretching)],
+            Insets {
+                top: 3.0,
+                bottom: 3.0,
+                ..padding
+            },
+        ),
+        Err(NinePatchError::InvalidMargin)
+    );
+    for margin in [f32::NAN, f32::INFINITY, -1.0] {
+        assert_eq!(
+            NinePatchBuilder::new(4, 4)
+                .padding(Insets {
+                    left: margin,
+                    ..padding
+                })
+                .build(),
+            Err(NinePatchError::InvalidMargin)
+        );
+        assert_eq!(
+            NinePatchBuilder::new(4, 4)
+                .padding(Insets {
+                    bottom: margin,
+                    ..padding
+                })
+                .build(),
+            Err(NinePatchError::InvalidMargin)
+        );
+    }
+}
+
+fn targets(patches: &[Patch]) -> Vec<(f32, f32, f32, f32)> {
+    patches
+        .iter()
+        .map(|p| (p.target.left, p.target.top, p.target.right, p.target.bottom))
+        .collect()
+}
+
+#[test]
+fn scale_without_horizontal_stretch() {
+    let drawable = NinePatchBuilder::new(4, 4)
+        .v_stretch(1.0..3.0)
+        .build()
+        .unwrap();
+    let scale = |fallback| {
+        drawable.scale_to_with(
+            10,
+            10,
+            &ScaleOptions {
+                fallback,
+                ..ScaleOptions::default()
+            },
+        )
+    };
+
+    assert_eq!(
+        targets(&scale(StretchFallback::StretchAll).unwrap()),
+        [
+            (1.0, 1.0, 9.0, 2.0),
+            (1.0, 2.0, 9.0, 8.0),
+            (1.0, 8.0, 9.0, 9.0)
+        ]
+    );
+    assert_eq!(
+        drawable.scale_to(10, 10),
+        scale(StretchFallback::StretchAll).unwrap()
+    );
+    assert_eq!(
+        targets(&scale(StretchFallback::Center).unwrap()),
+        [
+            (3.0, 1.0, 7.0, 2.0),
+            (3.0, 2.0, 7.0, 8.0),
+            (3.0, 8.0, 7.0, 9.0)
+        ]
+    );
+    assert_eq!(
+        scale(StretchFallback::Error),
+        Err(NinePatchError::NoStretchSection {
+            axis: Axis::Horizontal
+        })
+    );
+}
+
+#[test]
+fn scale_without_vertical_stretch() {
+    let drawable = NinePatchBuilder::new(4, 4)
+        .h_stretch(1.0..3.0)
+        .build()
+        .unwrap();
+    let scale = |fallback| {
+        drawable.scale_to_with(
+            10,
+            10,
+            &ScaleOptions {
+                fallback,
+                ..ScaleOptions::default()
+            },
+        )
+    };
+
+    assert_eq!(
+        targets(&scale(StretchFallback::StretchAll).unwrap()),
+        [
+            (1.0, 1.0, 2.0, 9.0),
+            (2.0, 1.0, 8.0, 9.0),
+            (8.0, 1.0, 9.0, 9.0)
+        ]
+    );
+    assert_eq!(
+        targets(&scale(StretchFallback::Center).unwrap()),
+        [
+            (1.0, 3.0, 2.0, 7.0),
+            (2.0, 3.0, 8.0, 7.0),
+            (8.0, 3.0, 9.0, 7.0)
+        ]
+    );
+    assert_eq!(
+        scale(StretchFallback::Error),
+        Err(NinePatchError::NoStretchSection {
+            axis: Axis::Vertical
+        })
+    );
+}
+
+#[test]
+fn scale_with_density() {
+    let drawable = NinePatchDrawable::new(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7).unwrap();
+    let options = ScaleOptions {
+        density: 2.0,
+        ..ScaleOptions::default()
+    };
+    assert_eq!(
+        targets(&drawable.scale_to_with(14, 12, &options).unwrap()),
+        [
+            (1.0, 1.0, 3.0, 3.0),
+            (3.0, 1.0, 11.0, 3.0),
+            (11.0, 1.0, 13.0, 3.0),
+            (1.0, 3.0, 3.0, 9.0),
+            (3.0, 3.0, 11.0, 9.0),
+            (11.0, 3.0, 13.0, 9.0),
+            (1.0, 9.0, 3.0, 11.0),
+            (3.0, 9.0, 11.0, 11.0),
+            (11.0, 9.0, 13.0, 11.0)
+        ]
+    );
+    assert_eq!(
+        drawable.content_rect(14, 12, &options),
+        RectF {
+            left: 3.0,
+            top: 3.0,
+            right: 11.0,
+            bottom: 9.0
+        }
+    );
+    assert_eq!(
+        drawable.scale_to_with(14, 5, &options),
+        Err(NinePatchError::TargetTooSmall {
+            axis: Axis::Vertical
+        })
+    );
+    for density in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        let options = ScaleOptions {
+            density,
+            ..ScaleOptions::default()
+        };
+        assert_eq!(
+            drawable.scale_to_with(14, 12, &options),
+            Err(NinePatchError::InvalidDensity)
+        );
+    }
+}
+
+#[test]
+fn density_set() {
+    let insets = |inset| Insets {
+        left: inset,
+        top: inset,
+        right: inset,
+        bottom: inset,
+    };
+    let mdpi = NinePatchBuilder::new(6, 6)
+        .insets(insets(2.0))
+        .build()
+        .unwrap();
+    let xhdpi = NinePatchBuilder::new(12, 12)
+        .insets(insets(4.0))
+        .build()
+        .unwrap();
+    let xxhdpi = NinePatchBuilder::new(18, 18)
+        .insets(insets(6.0))
+        .build()
+        .unwrap();
+    let mut set = NinePatchSet::new();
+    set.insert(1.0, mdpi).unwrap();
+    set.insert(3.0, xxhdpi).unwrap();
+    set.insert(2.0, xhdpi).unwrap();
+    assert_eq!(
+        set.iter().map(|(d, _)| d).collect::<Vec<_>>(),
+        [1.0, 2.0, 3.0]
+    );
+
+    assert_eq!(set.select(2.0, 40, 40).unwrap().0, 2.0);
+    assert_eq!(set.select(1.5, 40, 40).unwrap().0, 2.0);
+    assert_eq!(set.select(4.0, 40, 40).unwrap().0, 3.0);
+    assert_eq!(set.select(4.0, 10, 10), None);
+    assert_eq!(
+        targets(&set.scale_to(1.5, 20, 20).unwrap())[..3],
+        [
+            (1.0, 1.0, 4.0, 4.0),
+            (4.0, 1.0, 16.0, 4.0),
+            (16.0, 1.0, 19.0, 4.0)
+        ]
+    );
+
+    let mismatched = NinePatchBuilder::new(12, 12)
+        .insets(insets(2.0))
+        .build()
+        .unwrap();
+    assert_eq!(
+        set.insert(2.0, mismatched),
+        Err(NinePatchError::MismatchedSections {
+            axis: Axis::Horizontal
+        })
+    );
+    assert_eq!(
+        set.insert(0.0, NinePatchBuilder::new(1, 1).build().unwrap()),
+        Err(NinePatchError::InvalidDensity)
+    );
+}
+
+#[test]
+fn density_qualifiers() {
+    assert_eq!(crate::parse_density("drawable-xhdpi"), Some(2.0));
+    assert_eq!(crate::parse_density("drawable-land-hdpi"), Some(1.5));
+    assert_eq!(crate::parse_density("button@3x.9.png"), Some(3.0));
+    assert_eq!(crate::parse_density("button@1.5x.9.png"), Some(1.5));
+    assert_eq!(crate::parse_density("button@0.75x.png"), Some(0.75));
+    assert_eq!(crate::parse_density("button@2.png"), None);
+    assert_eq!(crate::parse_density("drawable"), None);
+}
+
+#[test]
+fn render_nine_patch() {
+    let bitmap = bitmap_from_monochrome(&NINE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let monochrome = |pixels: Vec<u8>| {
+        pixels
+            .chunks(4)
+            .map(|p| match p {
+                [1, 1, 1, 0] => 1,
+                [0x80, 0x80, 0x80, 0] => 6,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<u8>>()
+    };
+
+    let pixels = crate::render(&bitmap, 36, &drawable, 5, 5, &RenderOptions::default()).unwrap();
+    #[rustfmt::skip]
+    assert_eq!(monochrome(pixels), [
+        6, 1, 1, 1, 6,
+        1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1,
+        6, 1, 1, 1, 6,
+    ]);
+
+    let pixels = crate::render(&bitmap, 36, &drawable, 8, 3, &RenderOptions::default()).unwrap();
+    #[rustfmt::skip]
+    assert_eq!(monochrome(pixels), [
+        6, 1, 1, 1, 1, 1, 1, 6,
+        1, 1, 1, 1, 1, 1, 1, 1,
+        6, 1, 1, 1, 1, 1, 1, 6,
+    ]);
+
+    // Sections narrower than a source pixel.
+    let drawable = NinePatchBuilder::new(4, 4)
+        .h_stretch(1.2..1.7)
+        .v_stretch(1.0..3.0)
+        .build()
+        .unwrap();
+    drawable.validate().unwrap();
+    let mut bitmap = vec![0; 6 * 6 * 4];
+    for y in 1..5 {
+        for x in 1..5 {
+            bitmap[(y * 6 + x) * 4..(y * 6 + x) * 4 + 4]
+                .copy_from_slice(&[x as u8, y as u8, 0, 0xFF]);
+        }
+    }
+    let pixels = crate::render(&bitmap, 24, &drawable, 10, 10, &RenderOptions::default()).unwrap();
+    assert!(pixels
+        .chunks(4)
+        .all(|p| (1..5).contains(&p[0]) && (1..5).contains(&p[1])));
+    // The stretching column samples column 2, where the section starts.
+    let row: Vec<u8> = pixels[..10 * 4].chunks(4).map(|p| p[0]).collect();
+    assert_eq!(row, [1, 2, 2, 2, 2, 2, 2, 2, 3, 4]);
+}
+
+#[test]
+fn optical_insets() {
+    #[rustfmt::skip]
+    let bitmap_monochrome = [
+        0, 9, 0, 0, 0, 9, 0, 0, 0,
+        9, 6, 1, 1, 1, 6, 2, 0, 0,
+        0, 1, 1, 1, 1, 1, 9, 0, 0,
+        0, 1, 1, 1, 1, 1, 0, 0, 0,
+        0, 1, 1, 1, 1, 1, 9, 0, 0,
+        9, 6, 1, 1, 1, 6, 2, 0, 0,
+        0, 2, 0, 0, 0, 2, 0, 0, 0,
+    ];
+    let drawable =
+        NinePatchDrawable::new(&bitmap_from_monochrome(&bitmap_monochrome), 36, 7, 7).unwrap();
+    assert_eq!(1.0, drawable.margin_left);
+    assert_eq!(2.0, drawable.margin_top);
+    assert_eq!(1.0, drawable.margin_right);
+    assert_eq!(2.0, drawable.margin_bottom);
+    assert_eq!(
+        drawable.optical_insets,
+        Insets {
+            left: 1.0,
+            top: 1.0,
+            right: 1.0,
+            bottom: 1.0
+        }
+    );
+
+    // Red on the top and left borders is an ordinary stretch marker.
+    #[rustfmt::skip]
+    let bitmap_monochrome = [
+        0, 9, 2, 2, 2, 9, 0, 0, 0,
+        9, 6, 1, 1, 1, 6, 0, 0, 0,
+        2, 1, 1, 1, 1, 1, 9, 0, 0,
+        2, 1, 1, 1, 1, 1, 9, 0, 0,
+        2, 1, 1, 1, 1, 1, 9, 0, 0,
+        9, 6, 1, 1, 1, 6, 0, 0, 0,
+        0, 0, 9, 9, 9, 0, 0, 0, 0,
+    ];
+    let red = NinePatchDrawable::new(&bitmap_from_monochrome(&bitmap_monochrome), 36, 7, 7);
+    let plain = NinePatchDrawable::new(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7);
+    assert_eq!(red, plain);
+}
+
+#[test]
+fn render_debug_overlay() {
+    let bitmap = bitmap_from_monochrome(&NINE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let pixels =
+        crate::render_debug(&bitmap, 36, &drawable, 9, 9, &RenderOptions::default()).unwrap();
+    let pixel = |x: usize, y: usize| &pixels[(y * 9 + x) * 4..(y * 9 + x) * 4 + 4];
+
+    // Stretching in both directions.
+    assert_eq!(pixel(4, 4), [0xFF, 0x00, 0xFF, 0x60]);
+    // Content box.
+    assert_eq!(pixel(1, 4), [0x00, 0x00, 0xFF, 0xFF]);
+    assert_eq!(pixel(7, 7), [0x00, 0x00, 0xFF, 0xFF]);
+    // Optical bounds, the whole image.
+    assert_eq!(pixel(0, 4), [0xFF, 0x00, 0x00, 0xFF]);
+    assert_eq!(pixel(8, 8), [0xFF, 0x00, 0x00, 0xFF]);
+}
+
+#[test]
+fn css_border_image() {
+    let drawable = NinePatchBuilder::new(40, 30)
+        .insets(Insets {
+            left: 10.0,
+            top: 8.0,
+            right: 12.0,
+            bottom: 6.0,
+        })
+        .padding(Insets {
+            left: 14.0,
+            top: 8.0,
+            right: 16.0,
+            bottom: 4.0,
+        })
+        .build()
+        .unwrap();
+    assert_eq!(
+        crate::to_css(&drawable, "button.png").unwrap(),
+        "border-style: solid;\n\
+         border-width: 8px 12px 6px 10px;\n\
+         border-image-source: url(\"button.png\");\n\
+         border-image-slice: 8 12 6 10 fill;\n\
+         border-image-width: 8px 12px 6px 10px;\n\
+         border-image-repeat: stretch stretch;\n\
+         padding: 0px 4px 0px 4px;\n"
+    );
+    let html = crate::html_preview(&drawable, "button.png", &[(40, 30), (200, 60)]).unwrap();
+    assert!(html.contains("style=\"width: 200px; height: 60px\""));
+
+    let url = "a\"b\\c</style><script>\n";
+    let css = crate::to_css(&drawable, url).unwrap();
+    assert!(css.contains(r#"url("a\"b\\c\3c /style\3e \3c script\3e \a ");"#));
+    let html = crate::html_preview(&drawable, url, &[(40, 30)]).unwrap();
+    assert_eq!(html.matches("</style>").count(), 1);
+    assert!(!html.contains("<script>"));
+
+    let drawable =
+        NinePatchDrawable::new(&bitmap_from_monochrome(&TWENTY_FIVE_PATCH), 36, 7, 7).unwrap();
+    assert_eq!(
+        crate::to_css(&drawable, "button.png"),
+        Err(NinePatchError::UnsupportedSections {
+            axis: Axis::Horizontal
+        })
+    );
+}
+
+#[test]
+fn svg_nine_patch() {
+    let drawable = NinePatchDrawable::new(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7).unwrap();
+    let svg = crate::to_svg(
+        &drawable,
+        b"nine",
+        "image/png",
+        12,
+        12,
+        &ScaleOptions::default(),
+    )
+    .unwrap();
+    let lines: Vec<&str> = svg.lines().collect();
+    assert_eq!(lines.len(), 1 + 3 + 9 + 1);
+    assert!(lines[0].starts_with("<svg "));
+    assert!(lines[0].ends_with("width=\"12\" height=\"12\" viewBox=\"0 0 12 12\">"));
+    assert_eq!(
+        lines[2],
+        "<image id=\"nine-patch\" width=\"7\" height=\"7\" \
+         xlink:href=\"data:image/png;base64,bmluZQ==\"/>"
+    );
+    assert_eq!(
+        lines[4],
+        "<svg x=\"0\" y=\"0\" width=\"1\" height=\"1\" viewBox=\"1 1 1 1\" \
+         preserveAspectRatio=\"none\"><use xlink:href=\"#nine-patch\"/></svg>"
+    );
+    assert_eq!(
+        lines[8],
+        "<svg x=\"1\" y=\"1\" width=\"10\" height=\"10\" viewBox=\"2 2 3 3\" \
+         preserveAspectRatio=\"none\"><use xlink:href=\"#nine-patch\"/></svg>"
+    );
+    assert_eq!(lines[13], "</svg>");
+}
+
+#[test]
+fn svg_twenty_five_patch() {
+    let mut drawable =
+        NinePatchDrawable::new(&bitmap_from_monochrome(&TWENTY_FIVE_PATCH), 36, 7, 7).unwrap();
+    drawable.h_sections[1].kind = Tiling;
+    let svg = crate::to_svg(
+        &drawable,
+        b"twenty-five",
+        "image/png",
+        12,
+        12,
+        &ScaleOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(svg.matches("<svg ").count(), 1 + 20);
+    assert_eq!(svg.matches("<pattern ").count(), 5);
+    assert_eq!(svg.matches("<rect ").count(), 5);
+    assert!(svg.contains(
+        "<pattern id=\"tile-1\" patternUnits=\"userSpaceOnUse\" x=\"1\" y=\"0\" \
+         width=\"1\" height=\"1\" viewBox=\"2 1 1 1\" preserveAspectRatio=\"none\">\
+         <use xlink:href=\"#nine-patch\"/></pattern>"
+    ));
+    assert!(
+        svg.contains("<rect x=\"1\" y=\"0\" width=\"4.5\" height=\"1\" fill=\"url(#tile-1)\"/>")
+    );
+}
+
+#[test]
+fn engine_slice_metadata() {
+    use crate::{GodotPatchMargins, UnitySpriteBorder};
+
+    let drawable = NinePatchBuilder::new(40, 30)
+        .insets(Insets {
+            left: 10.0,
+            top: 8.0,
+            right: 12.0,
+            bottom: 6.0,
+        })
+        .build()
+        .unwrap();
+    let godot = GodotPatchMargins::from_drawable(&drawable).unwrap();
+    assert_eq!(
+        godot,
+        GodotPatchMargins {
+            left: 10,
+            top: 8,
+            right: 12,
+            bottom: 6
+        }
+    );
+    assert_eq!(godot.to_drawable(40, 30).unwrap(), drawable);
+
+    let meta = "TextureImporter:\n  spriteMode: 1\n  spriteBorder: {x: 10, y: 6, z: 12, w: 8}\n";
+    let unity = UnitySpriteBorder::parse_meta(meta).unwrap().unwrap();
+    assert_eq!(unity, UnitySpriteBorder::from_drawable(&drawable).unwrap());
+    assert_eq!(unity.to_drawable(40, 30).unwrap(), drawable);
+    assert_eq!(
+        UnitySpriteBorder::parse_meta("TextureImporter:\n"),
+        Ok(None)
+    );
+    assert_eq!(
+        UnitySpriteBorder::parse_meta("a:\n  spriteBorder: {x: 1, q: 2}\n"),
+        Err(NinePatchError::Syntax { line: 2 })
+    );
+
+    let twenty_five =
+        NinePatchDrawable::new(&bitmap_from_monochrome(&TWENTY_FIVE_PATCH), 36, 7, 7).unwrap();
+    assert_eq!(
+        GodotPatchMargins::from_drawable(&twenty_five),
+        Err(NinePatchError::UnsupportedSections {
+            axis: Axis::Horizontal
+        })
+    );
+}
+
+#[test]
+fn libgdx_atlas() {
+    use crate::AtlasRegion;
+
+    let legacy = "
+ui.png
+size: 256, 128
+format: RGBA8888
+filter: Nearest,Nearest
+repeat: none
+button
+  rotate: false
+  xy: 2, 2
+  size: 40, 30
+  split: 10, 12, 8, 6
+  pad: 14, -1, 8, 4
+  orig: 40, 30
+  offset: 0, 0
+  index: -1
+icon
+  rotate: false
+  xy: 44, 2
+  size: 16, 16
+  orig: 16, 16
+  offset: 0, 0
+  index: -1
+";
+    let current = "ui.png
+size:256,128
+filter:Nearest,Nearest
+button
+bounds:2,2,40,30
+split:10,12,8,6
+pad:14,-1,8,4
+icon
+bounds:44,2,16,16
+";
+    let regions = crate::parse_atlas(legacy).unwrap();
+    assert_eq!(regions, crate::parse_atlas(current).unwrap());
+    assert_eq!(
+        regions[0],
+        AtlasRegion {
+            page: "ui.png".into(),
+            name: "button".into(),
+            x: 2,
+            y: 2,
+            width: 40,
+            height: 30,
+            split: Some([10, 12, 8, 6]),
+            pad: Some([14, -1, 8, 4]),
+        }
+    );
+
+    let button = regions[0].to_drawable().unwrap();
+    assert_eq!(
+        button.slice_insets().unwrap(),
+        Insets {
+            left: 10.0,
+            top: 8.0,
+            right: 12.0,
+            bottom: 6.0
+        }
+    );
+    assert_eq!(button.margin_right, 12.0);
+    let exported = AtlasRegion::from_drawable("ui.png", "button", &button).unwrap();
+    assert_eq!(exported.split, Some([10, 12, 8, 6]));
+    assert_eq!(exported.pad, Some([14, 12, 8, 4]));
+
+    let icon = regions[1].to_drawable().unwrap();
+    assert_eq!(icon.h_sections.len(), 1);
+    assert_eq!(icon.h_sections[0].kind, Fixed);
+
+    assert_eq!(
+        crate::parse_atlas("ui.png\nbutton\nsplit: 1, 2\n"),
+        Err(NinePatchError::Syntax { line: 3 })
+    );
+}
+
+#[test]
+fn android_nine_patch_resource() {
+    use crate::{NinePatchResource, Tint, TintMode};
+
+    let xml = r##"<?xml version="1.0" encoding="utf-8"?>
+<!-- A tinted button. -->
+<nine-patch xmlns:android="http://schemas.android.com/apk/res/android"
+    android:src="@drawable/button"
+    android:dither="false"
+    android:tint="#80FF0000"
+    android:tintMode="src_over"
+    android:autoMirrored="true" />
+"##;
+    let resource = NinePatchResource::parse(xml).unwrap();
+    assert_eq!(
+        resource,
+        NinePatchResource {
+            src: "@drawable/button".into(),
+            dither: false,
+            tint: Some([0xFF, 0x00, 0x00, 0x80]),
+            tint_mode: TintMode::SrcOver,
+            auto_mirrored: true,
+        }
+    );
+    assert_eq!(
+        resource.render_options().tint,
+        Some(Tint {
+            color: [0xFF, 0x00, 0x00, 0x80],
+            mode: TintMode::SrcOver
+        })
+    );
+
+    let (bitmap, drawable) = resource
+        .resolve(|src| {
+            assert_eq!(src, "@drawable/button");
+            let cropped: Vec<u8> = NINE_PATCH
+                .chunks(9)
+                .flat_map(|row| &row[..7])
+                .copied()
+                .collect();
+            Ok((android_bitmap_from_monochrome(&cropped, 7), 7, 7))
+        })
+        .unwrap();
+    let plain = NinePatchDrawable::new(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7);
+    assert_eq!(Ok(&drawable), plain.as_ref());
+    let pixels = crate::render(&bitmap, 28, &drawable, 5, 5, &resource.render_options()).unwrap();
+    assert_eq!(&pixels[..4], [0xFF, 0x00, 0x00, 0x80]);
+
+    let defaults =
+        NinePatchResource::parse("<nine-patch android:src='@drawable/a&amp;b'/>").unwrap();
+    assert_eq!(defaults.src, "@drawable/a&b");
+    assert!(defaults.dither);
+    assert_eq!(defaults.tint_mode, TintMode::SrcIn);
+    assert_eq!(
+        NinePatchResource::parse("<nine-patch android:src=\"@drawable/a\" android:alpha=\"0.5\"/>"),
+        Err(NinePatchError::UnsupportedAttribute("android:alpha".into()))
+    );
+    assert_eq!(
+        NinePatchResource::parse("<nine-patch/>"),
+        Err(NinePatchError::MissingAttribute("android:src".into()))
+    );
+    assert_eq!(
+        NinePatchResource::parse("<bitmap android:src=\"@drawable/a\"/>"),
+        Err(NinePatchError::UnsupportedElement("bitmap".into()))
+    );
+    assert_eq!(
+        NinePatchResource::parse("<nine-patch\n android:tint=\"red\"/>"),
+        Err(NinePatchError::Syntax { line: 1 })
+    );
+    for reference in ["@color/accent", "?attr/colorAccent"] {
+        assert_eq!(
+            NinePatchResource::parse(&format!(
+                "<nine-patch android:src=\"@drawable/a\" android:tint=\"{reference}\"/>"
+            )),
+            Err(NinePatchError::UnresolvedReference {
+                attribute: "android:tint".into(),
+                reference: reference.into(),
+            })
+        );
+    }
+}
+
+#[test]
+fn tint_modes() {
+    use crate::{Tint, TintMode::*};
+
+    let tint = |mode| Tint {
+        color: [0xFF, 0x00, 0x00, 0x80],
+        mode,
+    };
+    let pixel = [0x00, 0x00, 0xFF, 0x80];
+    assert_eq!(tint(SrcOver).apply(pixel), [170, 0, 85, 192]);
+    assert_eq!(tint(SrcIn).apply(pixel), [255, 0, 0, 64]);
+    assert_eq!(tint(SrcAtop).apply(pixel), [128, 0, 127, 128]);
+    assert_eq!(tint(Multiply).apply(pixel), [0, 0, 0, 64]);
+    assert_eq!(tint(Screen).apply(pixel), [170, 0, 170, 192]);
+    assert_eq!(tint(Add).apply(pixel), [128, 0, 128, 255]);
+    assert_eq!(tint(SrcIn).apply([0x12, 0x34, 0x56, 0x00]), [0, 0, 0, 0]);
+}
+
+#[test]
+fn color_filters() {
+    use crate::{ColorMatrix, PixelFormat, Tint, TintMode};
+
+    assert_eq!(
+        ColorMatrix::IDENTITY.apply([0x12, 0x34, 0x56, 0x78]),
+        [0x12, 0x34, 0x56, 0x78]
+    );
+    let gray = ColorMatrix::saturation(0.0).apply([0xFF, 0x00, 0x00, 0xFF]);
+    assert_eq!(gray, [54, 54, 54, 0xFF]);
+
+    // A BGRA bitmap tinted with an RGBA color.
+    let mut bitmap = bitmap_from_monochrome(&NINE_PATCH);
+    for pixel in bitmap.chunks_mut(4) {
+        pixel[3] = 0xFF;
+    }
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let options = RenderOptions {
+        format: PixelFormat::Bgra,
+        tint: Some(Tint {
+            color: [0xFF, 0x00, 0x00, 0xFF],
+            mode: TintMode::SrcIn,
+        }),
+        ..RenderOptions::default()
+    };
+    let pixels = crate::render(&bitmap, 36, &drawable, 6, 6, &options).unwrap();
+    assert!(pixels
+        .chunks(4)
+        .all(|pixel| pixel == [0x00, 0x00, 0xFF, 0xFF]));
+
+    // Filtering the bitmap itself keeps the markers.
+    let mut filtered = bitmap.clone();
+    crate::filter_content(&mut filtered, 36, 7, 7, &options).unwrap();
+    assert_eq!(
+        NinePatchDrawable::new(&filtered, 36, 7, 7).unwrap(),
+        drawable
+    );
+    assert_eq!(filtered[..36], bitmap[..36]);
+    assert_eq!(filtered[40..44], [0x00, 0x00, 0xFF, 0xFF]);
+}
+
+#[rustfmt::skip]
+const ASYMMETRIC_PATCH: [u8; 63] = [
+    0, 9, 9, 0, 0, 9, 0, 0, 0,
+    9, 6, 6, 1, 1, 1, 0, 0, 0,
+    0, 1, 1, 1, 1, 1, 9, 0, 0,
+    0, 1, 1, 1, 1, 1, 9, 0, 0,
+    9, 1, 1, 1, 1, 6, 9, 0, 0,
+    9, 6, 1, 1, 1, 6, 0, 0, 0,
+    0, 0, 0, 9, 9, 0, 0, 0, 0,
+];
+
+#[test]
+fn mirrored() {
+    let bitmap = bitmap_from_monochrome(&ASYMMETRIC_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let mirrored = drawable.mirrored(Axis::Horizontal);
+    assert_eq!(
+        mirrored.h_sections,
+        [
+            Section {
+                start: 0.0,
+                len: 1.0,
+                kind: Fixed
+            },
+            Section {
+                start: 1.0,
+                len: 2.0,
+                kind: Stretching
+            },
+            Section {
+                start: 3.0,
+                len: 2.0,
+                kind: Fixed
+            },
+        ]
+    );
+    assert_eq!(
+        (mirrored.margin_left, mirrored.margin_right),
+        (drawable.margin_right, drawable.margin_left)
+    );
+    assert_eq!(mirrored.mirrored(Axis::Horizontal), drawable);
+
+    for axis in [Axis::Horizontal, Axis::Vertical] {
+        let mirrored_bitmap = crate::mirror_bitmap(&bitmap, 36, 7, 7, axis).unwrap();
+        assert_eq!(
+            NinePatchDrawable::new(&mirrored_bitmap, 28, 7, 7).unwrap(),
+            drawable.mirrored(axis)
+        );
+    }
+
+    let mirrored_bitmap = crate::mirror_bitmap(&bitmap, 36, 7, 7, Axis::Horizontal).unwrap();
+    let rtl = RenderOptions {
+        rtl: true,
+        ..RenderOptions::default()
+    };
+    assert_eq!(
+        crate::render(&bitmap, 36, &drawable, 9, 6, &rtl).unwrap(),
+        crate::render(
+            &mirrored_bitmap,
+            28,
+            &mirrored,
+            9,
+            6,
+            &RenderOptions::default()
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn rotated() {
+    let bitmap = bitmap_from_monochrome(&ASYMMETRIC_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let quarter = drawable.rotated(1);
+    assert_eq!(
+        quarter.h_sections,
+        drawable.mirrored(Axis::Vertical).v_sections
+    );
+    assert_eq!(quarter.v_sections, drawable.h_sections);
+    assert_eq!(
+        (
+            quarter.margin_left,
+            quarter.margin_top,
+            quarter.margin_right,
+            quarter.margin_bottom
+        ),
+        (
+            drawable.margin_bottom,
+            drawable.margin_left,
+            drawable.margin_top,
+            drawable.margin_right
+        )
+    );
+    assert_eq!(drawable.rotated(4), drawable);
+    assert_eq!(drawable.rotated(-1), drawable.rotated(3));
+    assert_eq!(
+        drawable.rotated(2),
+        drawable.mirrored(Axis::Horizontal).mirrored(Axis::Vertical)
+    );
+
+    for quarter_turns in 0..4 {
+        let rotated_bitmap = crate::rotate_bitmap(&bitmap, 36, 7, 7, quarter_turns).unwrap();
+        assert_eq!(
+            NinePatchDrawable::new(&rotated_bitmap, 28, 7, 7).unwrap(),
+            drawable.rotated(quarter_turns)
+        );
+    }
+
+    // Rendering the rotated drawable is the same as rotating the rendering.
+    let options = RenderOptions::default();
+    let rendered = crate::render(&bitmap, 36, &drawable, 9, 7, &options).unwrap();
+    let rotated_bitmap = crate::rotate_bitmap(&bitmap, 36, 7, 7, 1).unwrap();
+    let rotated_rendered = crate::render(&rotated_bitmap, 28, &quarter, 7, 9, &options).unwrap();
+    let mut expected = vec![];
+    for y in 0..9 {
+        for x in 0..7 {
+            let o = ((6 - x) * 9 + y) * 4;
+            expected.extend_from_slice(&rendered[o..o + 4]);
+        }
+    }
+    assert_eq!(rotated_rendered, expected);
+}
+
+#[test]
+fn patches_iterator() {
+    for fixture in [NINE_PATCH, TWENTY_FIVE_PATCH] {
+        let bitmap = bitmap_from_monochrome(&fixture);
+        let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+        let patches = drawable.patches(12, 9);
+        assert_eq!(patches.len(), drawable.scale_to(12, 9).len());
+        assert_eq!(patches.collect::<Vec<_>>(), drawable.scale_to(12, 9));
+
+        let mut reused = Vec::with_capacity(32);
+        let capacity = reused.capacity();
+        for (width, height) in [(12, 9), (20, 30), (7, 7)] {
+            drawable
+                .scale_into(width, height, &ScaleOptions::default(), &mut reused)
+                .unwrap();
+            assert_eq!(reused, drawable.scale_to(width, height));
+        }
+        assert_eq!(reused.capacity(), capacity);
+    }
+
+    let bitmap = bitmap_from_monochrome(&NINE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let mut patches = drawable.patches(7, 7);
+    patches.nth(8);
+    assert_eq!(patches.len(), 0);
+    assert_eq!(patches.next(), None);
+}
+
+#[test]
+fn layout_cache() {
+    let bitmap = bitmap_from_monochrome(&TWENTY_FIVE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let layout = NinePatchLayout::new(&drawable, 12, 9, &ScaleOptions::default()).unwrap();
+    assert_eq!((layout.columns(), layout.rows()), (5, 5));
+    assert_eq!(
+        layout.patches().collect::<Vec<_>>(),
+        drawable.scale_to(12, 9)
+    );
+    assert_eq!(layout.patch(4, 4), drawable.scale_to(12, 9).pop());
+    assert_eq!(layout.patch(5, 0), None);
+
+    let mut cache = LayoutCache::new(drawable.clone(), ScaleOptions::default(), 2);
+    let first = cache.get(12, 9).unwrap();
+    assert_eq!(*first, layout);
+    assert!(std::sync::Arc::ptr_eq(&first, &cache.get(12, 9).unwrap()));
+    cache.get(20, 20).unwrap();
+    // 12 x 9 was used more recently than 20 x 20, so that one is evicted.
+    cache.get(12, 9).unwrap();
+    cache.get(30, 30).unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(std::sync::Arc::ptr_eq(&first, &cache.get(12, 9).unwrap()));
+    assert_eq!(
+        cache.get(3, 3),
+        Err(NinePatchError::TargetTooSmall {
+            axis: Axis::Horizontal
+        })
+    );
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn layout_edges() {
+    let bitmap = bitmap_from_monochrome(&TWENTY_FIVE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let layout = NinePatchLayout::new(&drawable, 13, 10, &ScaleOptions::default()).unwrap();
+    assert_eq!(layout.source_column_edges(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(layout.source_row_edges(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(layout.column_edges(), [1.0, 2.0, 6.0, 7.0, 11.0, 12.0]);
+    assert_eq!(layout.row_edges(), [1.0, 2.0, 4.5, 5.5, 8.0, 9.0]);
+    assert_eq!(
+        layout.column_kinds(),
+        [Fixed, Stretching, Fixed, Stretching, Fixed]
+    );
+
+    // Adjacent patches share their edges bit for bit, also with the iterator.
+    let patches: Vec<_> = drawable.patches(13, 10).collect();
+    for row in 0..layout.rows() {
+        for column in 0..layout.columns() {
+            let patch = layout.patch(column, row).unwrap();
+            assert_eq!(patch, patches[row * layout.columns() + column]);
+            assert_eq!(patch.target.left, layout.column_edges()[column]);
+            assert_eq!(patch.target.bottom, layout.row_edges()[row + 1]);
+            if let Some(next) = layout.patch(column + 1, row) {
+                assert_eq!(patch.target.right.to_bits(), next.target.left.to_bits());
+            }
+        }
+    }
+}
+
+#[test]
+fn scalar_types() {
+    let bitmap = bitmap_from_monochrome(&TWENTY_FIVE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let options = ScaleOptions::default();
+
+    let patches = drawable.scale_to_as::<f32>(12, 9, &options).unwrap();
+    assert_eq!(patches, drawable.scale_to(12, 9));
+
+    // The 2.5 and 3.5 pixel stretching sections are rounded at the edges, so
+    // the patches still cover the target without gaps.
+    let layout = NinePatchLayout::<i32>::from_drawable(&drawable, 12, 10, &options).unwrap();
+    assert_eq!(layout.row_edges(), [1, 2, 5, 6, 8, 9]);
+    assert_eq!(layout.column_edges(), [1, 2, 6, 7, 10, 11]);
+    let patches = drawable.scale_to_as::<u32>(12, 10, &options).unwrap();
+    assert_eq!(
+        patches[6].target,
+        Rect {
+            left: 2,
+            top: 2,
+            right: 6,
+            bottom: 5
+        }
+    );
+
+    // Beyond 2^24 pixels f32 can no longer tell the edges apart.
+    let width = (1 << 25) + 3;
+    let layout = NinePatchLayout::<f64>::from_drawable(&drawable, width, 7, &options).unwrap();
+    assert_eq!(layout.column_edges()[2] + 1.0, layout.column_edges()[3]);
+    assert_eq!(layout.column_edges()[5], width as f64 - 1.0);
+
+    assert_eq!(
+        RectF {
+            left: 0.5,
+            top: 1.4,
+            right: 2.5,
+            bottom: -1.6
+        }
+        .cast::<i32>(),
+        Rect {
+            left: 1,
+            top: 1,
+            right: 3,
+            bottom: -2
+        }
+    );
+    assert_eq!(
+        Section {
+            start: 0.4,
+            len: 1.2,
+            kind: Fixed
+        }
+        .cast::<u32>(),
+        Section {
+            start: 0,
+            len: 2,
+            kind: Fixed
+        }
+    );
+}
+
+#[cfg(all(
+    feature = "euclid",
+    feature = "glam",
+    feature = "kurbo",
+    feature = "mint"
+))]
+#[test]
+fn geometry_interop() {
+    let bitmap = bitmap_from_monochrome(&NINE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let layout = NinePatchLayout::new(&drawable, 12, 9, &ScaleOptions::default()).unwrap();
+    let target = layout.patch(1, 1).unwrap().target;
+
+    let euclid_box: euclid::default::Box2D<f32> = target.into();
+    assert_eq!(
+        euclid_box,
+        euclid::default::Box2D::new((2.0, 2.0).into(), (10.0, 7.0).into())
+    );
+    assert_eq!(RectF::from(euclid_box), target);
+    let euclid_rect: euclid::default::Rect<f32> = target.into();
+    assert_eq!(euclid_rect.size, euclid::size2(8.0, 5.0));
+    assert_eq!(RectF::from(euclid_rect), target);
+    let offsets: euclid::default::SideOffsets2D<f32> = drawable.slice_insets().unwrap().into();
+    assert_eq!(Insets::from(offsets), drawable.slice_insets().unwrap());
+
+    let edges: glam::Vec4 = target.into();
+    assert_eq!(edges, glam::Vec4::new(2.0, 2.0, 10.0, 7.0));
+    assert_eq!(RectF::from(edges), target);
+    let (min, max): (glam::Vec2, glam::Vec2) = target.into();
+    assert_eq!(max - min, glam::Vec2::new(8.0, 5.0));
+
+    let kurbo_rect: kurbo::Rect = target.into();
+    assert_eq!(kurbo_rect, kurbo::Rect::new(2.0, 2.0, 10.0, 7.0));
+    assert_eq!(Rect::<i32>::from(kurbo_rect), target.cast::<i32>());
+
+    let (min, max): (mint::Point2<f32>, mint::Point2<f32>) = target.into();
+    assert_eq!((min.x, max.y), (2.0, 7.0));
+    assert_eq!(RectF::from((min, max)), target);
+
+    // Patches convert to their source and target rectangles.
+    let patch = || layout.patch(1, 1).unwrap();
+    let (source, _): (euclid::default::Box2D<f32>, _) = patch().into();
+    assert_eq!(
+        source,
+        euclid::default::Box2D::new((2.0, 2.0).into(), (5.0, 5.0).into())
+    );
+    let (source, target_edges): (glam::Vec4, glam::Vec4) = patch().into();
+    assert_eq!(source, glam::Vec4::new(2.0, 2.0, 5.0, 5.0));
+    assert_eq!(target_edges, edges);
+    let rects: Vec<(kurbo::Rect, kurbo::Rect)> = layout.patches().map(Into::into).collect();
+    assert_eq!(rects.len(), 9);
+    assert_eq!(rects[4], (kurbo::Rect::new(2.0, 2.0, 5.0, 5.0), kurbo_rect));
+    type Corners = (mint::Point2<f32>, mint::Point2<f32>);
+    let (_, (min, max)): (Corners, Corners) = patch().into();
+    assert_eq!(RectF::from((min, max)), target);
+}
+
+#[cfg(feature = "tiny-skia")]
+#[test]
+fn tiny_skia_backend() {
+    use tiny_skia::{Pixmap, PixmapPaint, Rect};
+
+    for fixture in [NINE_PATCH, TWENTY_FIVE_PATCH] {
+        // Opaque content so blending onto a transparent pixmap is a copy.
+        let mut bitmap = bitmap_from_monochrome(&fixture);
+        for y in 1..6 {
+            for x in 1..6 {
+                let o = y * 36 + x * 4;
+                bitmap[o..o + 4].copy_from_slice(&[x as u8 * 40, y as u8 * 40, 200, 255]);
+            }
+        }
+        let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+        let mut data = vec![];
+        for row in bitmap.chunks(36) {
+            data.extend_from_slice(&row[..28]);
+        }
+        let source = Pixmap::from_vec(data, tiny_skia::IntSize::from_wh(7, 7).unwrap()).unwrap();
+
+        // Odd sizes keep the twenty five patch edges on whole pixels.
+        for (width, height) in [(5, 7), (13, 9), (21, 5)] {
+            let expected = crate::render(
+                &bitmap,
+                36,
+                &drawable,
+                width,
+                height,
+                &RenderOptions::default(),
+            )
+            .unwrap();
+            let mut pixmap = Pixmap::new(width as u32, height as u32).unwrap();
+            crate::draw_nine_patch(
+                &mut pixmap.as_mut(),
+                &source,
+                &drawable,
+                Rect::from_xywh(0.0, 0.0, width as f32, height as f32).unwrap(),
+                &PixmapPaint::default(),
+            )
+            .unwrap();
+            assert_eq!(pixmap.data(), expected, "{width}x{height}");
+        }
+
+        // Fractional destinations are anti-aliased on the outside only.
+        let mut pixmap = Pixmap::new(16, 12).unwrap();
+        crate::draw_nine_patch(
+            &mut pixmap.as_mut(),
+            &source,
+            &drawable,
+            Rect::from_xywh(0.5, 0.5, 13.0, 9.0).unwrap(),
+            &PixmapPaint::default(),
+        )
+        .unwrap();
+        let alpha = |x: usize, y: usize| pixmap.data()[(y * 16 + x) * 4 + 3];
+        assert!(alpha(0, 0) > 0 && alpha(0, 0) < 255);
+        assert!(alpha(13, 5) > 0 && alpha(13, 5) < 255);
+        for y in 1..9 {
+            for x in 1..13 {
+                assert_eq!(alpha(x, y), 255, "{x},{y}");
+            }
+        }
+    }
+    // Sections narrower than a source pixel leave no holes.
+    let drawable = NinePatchBuilder::new(4, 4)
+        .h_stretch(1.6..1.9)
+        .v_stretch(1.0..3.0)
+        .build()
+        .unwrap();
+    let mut source = Pixmap::new(6, 6).unwrap();
+    source.fill(tiny_skia::Color::from_rgba8(40, 80, 200, 255));
+    let mut pixmap = Pixmap::new(10, 10).unwrap();
+    crate::draw_nine_patch(
+        &mut pixmap.as_mut(),
+        &source,
+        &drawable,
+        Rect::from_xywh(0.0, 0.0, 10.0, 10.0).unwrap(),
+        &PixmapPaint::default(),
+    )
+    .unwrap();
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 255));
+}
+
+#[test]
+fn transitions() {
+    let bitmap = bitmap_from_monochrome(&TWENTY_FIVE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let options = ScaleOptions::default();
+    let transition = NinePatchTransition::resize(&drawable, (7, 7), (15, 11), &options).unwrap();
+    assert_eq!(transition.patches(0.0), drawable.scale_to(7, 7));
+    assert_eq!(transition.patches(1.0), drawable.scale_to(15, 11));
+    assert_eq!(transition.size(0.5), (11.0, 9.0));
+    let half = transition.patches(0.5);
+    assert_eq!(
+        targets(&half[..5]),
+        [
+            (1.0, 1.0, 2.0, 2.0),
+            (2.0, 1.0, 5.0, 2.0),
+            (5.0, 1.0, 6.0, 2.0),
+            (6.0, 1.0, 9.0, 2.0),
+            (9.0, 1.0, 10.0, 2.0),
+        ]
+    );
+    assert_eq!(half, drawable.scale_to(11, 9));
+    // Frames between whole pixel sizes have a layout too.
+    assert_eq!(transition.size(0.125), (8.0, 7.5));
+    assert_eq!(
+        targets(&transition.patches(0.125)[1..2]),
+        [(2.0, 1.0, 3.5, 2.0)]
+    );
+
+    let pressed = NinePatchBuilder::new(5, 5)
+        .h_stretch(1.0..2.0)
+        .h_stretch(3.0..4.0)
+        .v_stretch(1.0..2.0)
+        .v_stretch(3.0..4.0)
+        .build()
+        .unwrap();
+    let cross_fade =
+        NinePatchTransition::cross_fade(&drawable, &pressed, (7, 7), (9, 9), &options).unwrap();
+    for (a, b) in cross_fade
+        .patches(0.25)
+        .iter()
+        .zip(cross_fade.to_patches(0.25))
+    {
+        assert_eq!(a.target, b.target);
+    }
+    assert_eq!(cross_fade.to_patches(1.0), pressed.scale_to(9, 9));
+
+    let nine_patch =
+        NinePatchDrawable::new(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7).unwrap();
+    assert_eq!(
+        NinePatchTransition::cross_fade(&drawable, &nine_patch, (7, 7), (9, 9), &options),
+        Err(NinePatchError::MismatchedSections {
+            axis: Axis::Horizontal
+        })
+    );
+}
+
+#[test]
+fn damage() {
+    let bitmap = bitmap_from_monochrome(&NINE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    let (small, wide) = (drawable.scale_to(7, 7), drawable.scale_to(9, 7));
+    assert_eq!(
+        crate::damaged_rects(&small, (0.0, 0.0), &small, (0.0, 0.0)),
+        []
+    );
+    // The left column stays in place.
+    assert_eq!(
+        crate::damaged_rects(&small, (0.0, 0.0), &wide, (0.0, 0.0)),
+        [RectF {
+            left: 2.0,
+            top: 1.0,
+            right: 8.0,
+            bottom: 6.0
+        }]
+    );
+    assert_eq!(
+        crate::damaged_rects(&small, (10.0, 0.0), &small, (20.0, 0.0)),
+        [
+            RectF {
+                left: 11.0,
+                top: 1.0,
+                right: 16.0,
+                bottom: 6.0
+            },
+            RectF {
+                left: 21.0,
+                top: 1.0,
+                right: 26.0,
+                bottom: 6.0
+            },
+        ]
+    );
+
+    let bitmap = bitmap_from_monochrome(&TWENTY_FIVE_PATCH);
+    let drawable = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    // The top row stays in place.
+    assert_eq!(
+        crate::damaged_rects(
+            &drawable.scale_to(7, 7),
+            (0.0, 0.0),
+            &drawable.scale_to(7, 9),
+            (0.0, 0.0)
+        ),
+        [RectF {
+            left: 1.0,
+            top: 2.0,
+            right: 6.0,
+            bottom: 8.0
+        }]
+    );
+    // Growing to the left moves everything but the top right corner, which
+    // is not a rectangle.
+    let damage = crate::damaged_rects(
+        &drawable.scale_to(7, 7),
+        (2.0, 0.0),
+        &drawable.scale_to(9, 9),
+        (0.0, 0.0),
+    );
+    assert_eq!(damage.len(), 2);
+    let covered: f32 = damage
+        .iter()
+        .map(|r| (r.right - r.left) * (r.bottom - r.top))
+        .sum();
+    assert_eq!(covered, 7.0 * 7.0 - 1.0);
+}
+
+#[test]
+fn detect_slices() {
+    // A 9 x 6 pill: rounded ends, a flat middle and a shadow on the bottom row.
+    let mut bitmap = vec![];
+    for y in 0..6 {
+        for x in 0..9 {
+            let pixel = match (x, y) {
+                (0 | 8, 0 | 4 | 5) => [0, 0, 0, 0],
+                (_, 5) => [0, 0, 0, 0x40],
+                (1 | 7, _) => [0x20, 0x60, 0xC0, 0x80],
+                _ => [0x20, 0x60, 0xC0, 0xFF],
+            };
+            bitmap.extend_from_slice(&pixel);
+        }
+    }
+    let detection = crate::detect_slices(&bitmap, 36, 9, 6).unwrap();
+    let drawable = &detection.drawable;
+    assert_eq!(
+        drawable.h_sections,
+        [
+            Section {
+                start: 0.0,
+                len: 2.0,
+                kind: Fixed
+            },
+            Section {
+                start: 2.0,
+                len: 5.0,
+                kind: Stretching
+            },
+            Section {
+                start: 7.0,
+                len: 2.0,
+                kind: Fixed
+            },
+        ]
+    );
+    assert_eq!(
+        drawable.v_sections,
+        [
+            Section {
+                start: 0.0,
+                len: 1.0,
+                kind: Fixed
+            },
+            Section {
+                start: 1.0,
+                len: 3.0,
+                kind: Stretching
+            },
+            Section {
+                start: 4.0,
+                len: 2.0,
+                kind: Fixed
+            },
+        ]
+    );
+    assert_eq!((drawable.margin_left, drawable.margin_top), (2.0, 1.0));
+    assert_eq!(detection.confidence, 0.5);
+
+    let marked_up = crate::mark_up(&bitmap, 36, 9, 6, drawable).unwrap();
+    assert_eq!(
+        NinePatchDrawable::new(&marked_up, 44, 11, 8).as_ref(),
+        Ok(drawable)
+    );
+
+    // Noise everywhere leaves nothing to stretch.
+    let noise: Vec<u8> = (0..9 * 6 * 4).map(|i| (i * 37 % 251) as u8).collect();
+    assert_eq!(
+        crate::detect_slices(&noise, 36, 9, 6).unwrap().confidence,
+        0.0
+    );
+}
+
+#[test]
+fn lint() {
+    let cropped: Vec<u8> = NINE_PATCH
+        .chunks(9)
+        .flat_map(|row| &row[..7])
+        .copied()
+        .collect();
+    let mut bitmap = android_bitmap_from_monochrome(&cropped, 7);
+    assert_eq!(crate::lint(&bitmap, 28, 7, 7), Ok(vec![]));
+    // The parser's own convention of transparent markers reads as unmarked.
+    assert_eq!(
+        crate::lint(&bitmap_from_monochrome(&NINE_PATCH), 36, 7, 7),
+        Err(NinePatchError::InvalidMargin)
+    );
+
+    let mut set = |x: usize, y: usize, pixel: [u8; 4]| {
+        bitmap[y * 28 + x * 4..y * 28 + x * 4 + 4].copy_from_slice(&pixel);
+    };
+    set(0, 0, [0, 0, 0, 0xFF]);
+    set(3, 0, [0, 0, 0, 0x80]);
+    // Text padding larger than the fixed corners is fine.
+    set(2, 6, [0, 0, 0, 0xFF]);
+    set(3, 6, [0, 0, 0, 0xFF]);
+    set(3, 2, [9, 9, 9, 0]);
+    set(2, 5, [0xFF, 0, 0, 0]);
+    assert_eq!(
+        crate::lint(&bitmap, 28, 7, 7),
+        Ok(vec![
+            LintWarning::OpaqueCorner { x: 0, y: 0 },
+            LintWarning::SemiTransparentMarker { x: 3, y: 0 },
+            LintWarning::NonUniformStretch {
+                axis: Axis::Horizontal,
+                index: 1,
+                x: 3,
+                y: 5
+            },
+            LintWarning::GradientStretch {
+                axis: Axis::Vertical,
+                index: 1,
+                x: 3,
+                y: 3
+            },
+        ])
+    );
+
+    // Padding that leaves no content is an error rather than a warning.
+    for x in 1..6 {
+        bitmap[6 * 28 + x * 4 + 3] = 0xFF;
+    }
+    assert_eq!(
+        crate::lint(&bitmap, 28, 7, 7),
+        Err(NinePatchError::InvalidMargin)
+    );
+}
+
+#[test]
+fn stretch_distribution() {
+    use std::sync::Arc;
+
+    // Stretching sections of 1 and 3 pixels around a fixed notch.
+    let drawable = NinePatchBuilder::new(8, 3)
+        .h_stretch(1.0..2.0)
+        .h_stretch(4.0..7.0)
+        .build()
+        .unwrap();
+    let widths = |width: usize, distribution: Option<Arc<dyn crate::StretchPolicy>>| {
+        let options = ScaleOptions {
+            distribution,
+            ..ScaleOptions::default()
+        };
+        let patches = drawable.scale_to_with(width, 5, &options).unwrap();
+        patches[..5]
+            .iter()
+            .map(|p| p.target.right - p.target.left)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(widths(16, None), [1.0, 2.5, 2.0, 7.5, 1.0]);
+    assert_eq!(
+        widths(16, Some(Arc::new(crate::Proportional))),
+        [1.0, 2.5, 2.0, 7.5, 1.0]
+    );
+    // Only the 6 pixels beyond the source length are shared.
+    assert_eq!(
+        widths(16, Some(Arc::new(crate::EqualShare))),
+        [1.0, 4.0, 2.0, 6.0, 1.0]
+    );
+    assert_eq!(
+        widths(10, Some(Arc::new(crate::EqualShare))),
+        [1.0, 1.0, 2.0, 3.0, 1.0]
+    );
+    // Smaller targets shrink proportionally.
+    assert_eq!(
+        widths(8, Some(Arc::new(crate::EqualShare))),
+        [1.0, 0.5, 2.0, 1.5, 1.0]
+    );
+    assert_eq!(
+        widths(
+            16,
+            Some(Arc::new(crate::StretchWeights {
+                horizontal: vec![3.0, 1.0],
+                vertical: vec![],
+            }))
+        ),
+        [1.0, 5.5, 2.0, 4.5, 1.0]
+    );
+    // A weight of zero keeps the source length.
+    assert_eq!(
+        widths(
+            16,
+            Some(Arc::new(crate::StretchWeights {
+                horizontal: vec![1.0],
+                vertical: vec![],
+            }))
+        ),
+        [1.0, 7.0, 2.0, 3.0, 1.0]
+    );
+    // Infinite weights count as zero.
+    assert_eq!(
+        widths(
+            16,
+            Some(Arc::new(crate::StretchWeights {
+                horizontal: vec![f32::INFINITY, 1.0],
+                vertical: vec![],
+            }))
+        ),
+        [1.0, 1.0, 2.0, 9.0, 1.0]
+    );
+    assert_eq!(
+        widths(
+            16,
+            Some(Arc::new(crate::StretchWeights {
+                horizontal: vec![f32::NAN, f32::NEG_INFINITY],
+                vertical: vec![],
+            }))
+        ),
+        [1.0, 2.5, 2.0, 7.5, 1.0]
+    );
+    // All weights zero falls back to proportional.
+    assert_eq!(
+        widths(16, Some(Arc::new(crate::StretchWeights::default()))),
+        [1.0, 2.5, 2.0, 7.5, 1.0]
+    );
+
+    let drawable = NinePatchBuilder::new(7, 5)
+        .h_stretch(1.0..2.0)
+        .h_stretch(3.0..4.0)
+        .h_stretch(5.0..6.0)
+        .build()
+        .unwrap();
+    let options = ScaleOptions {
+        distribution: Some(Arc::new(crate::CenterOnly)),
+        ..ScaleOptions::default()
+    };
+    let layout = NinePatchLayout::new(&drawable, 15, 7, &options).unwrap();
+    assert_eq!(
+        layout.column_edges(),
+        [1.0, 2.0, 3.0, 4.0, 11.0, 12.0, 13.0, 14.0]
+    );
+    assert_eq!(layout.row_edges(), [1.0, 6.0]);
+    // With two stretching sections both are in the middle.
+    let bitmap = bitmap_from_monochrome(&TWENTY_FIVE_PATCH);
+    let twenty_five = NinePatchDrawable::new(&bitmap, 36, 7, 7).unwrap();
+    assert_eq!(
+        twenty_five.scale_to_with(12, 12, &options),
+        twenty_five.scale_to_with(12, 12, &ScaleOptions::default())
+    );
+    assert_ne!(options, ScaleOptions::default());
+    assert_eq!(options.clone(), options);
+}
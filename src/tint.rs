@@ -0,0 +1,154 @@
+/// How a tint color is combined with the drawable, named after Android's
+/// `PorterDuff.Mode`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TintMode {
+    SrcOver,
+    /// Replace the color, keeping the alpha of the drawable. The default
+    /// Android tint mode.
+    #[default]
+    SrcIn,
+    SrcAtop,
+    Multiply,
+    Screen,
+    Add,
+}
+
+impl TintMode {
+    /// Parse an `android:tintMode` value such as `src_in`.
+    pub fn from_android(value: &str) -> Option<TintMode> {
+        match value {
+            "src_over" => Some(TintMode::SrcOver),
+            "src_in" => Some(TintMode::SrcIn),
+            "src_atop" => Some(TintMode::SrcAtop),
+            "multiply" => Some(TintMode::Multiply),
+            "screen" => Some(TintMode::Screen),
+            "add" => Some(TintMode::Add),
+            _ => None,
+        }
+    }
+}
+
+/// A tint color and the mode used to apply it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Tint {
+    /// Non-premultiplied RGBA.
+    pub color: [u8; 4],
+    pub mode: TintMode,
+}
+
+impl Tint {
+    /// Apply the tint to a non-premultiplied RGBA pixel.
+    pub fn apply(&self, pixel: [u8; 4]) -> [u8; 4] {
+        let s = premultiply(self.color);
+        let d = premultiply(pixel);
+        let (sa, da) = (s[3], d[3]);
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            out[i] = match self.mode {
+                TintMode::SrcOver => s[i] + d[i] * (1.0 - sa),
+                TintMode::SrcIn => s[i] * da,
+                TintMode::SrcAtop if i == 3 => da,
+                TintMode::SrcAtop => s[i] * da + d[i] * (1.0 - sa),
+                TintMode::Multiply => s[i] * d[i],
+                TintMode::Screen => s[i] + d[i] - s[i] * d[i],
+                TintMode::Add => (s[i] + d[i]).min(1.0),
+            };
+        }
+        unpremultiply(out)
+    }
+}
+
+/// A 4x5 color matrix in the layout of Android's `ColorMatrix`. Each row
+/// computes one channel of a non-premultiplied RGBA pixel with channels in
+/// the range 0 to 255:
+///
+/// ```text
+/// R' = m[0] * R + m[1] * G + m[2] * B + m[3] * A + m[4]
+/// G' = m[5] * R + m[6] * G + m[7] * B + m[8] * A + m[9]
+/// B' = m[10] * R + m[11] * G + m[12] * B + m[13] * A + m[14]
+/// A' = m[15] * R + m[16] * G + m[17] * B + m[18] * A + m[19]
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ColorMatrix(pub [f32; 20]);
+
+impl ColorMatrix {
+    #[rustfmt::skip]
+    pub const IDENTITY: ColorMatrix = ColorMatrix([
+        1.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ]);
+
+    /// A matrix that changes the saturation, `0.0` is grayscale and `1.0`
+    /// leaves the colors unchanged.
+    pub fn saturation(saturation: f32) -> ColorMatrix {
+        let inverse = 1.0 - saturation;
+        let r = 0.213 * inverse;
+        let g = 0.715 * inverse;
+        let b = 0.072 * inverse;
+        #[rustfmt::skip]
+        let matrix = [
+            r + saturation, g, b, 0.0, 0.0,
+            r, g + saturation, b, 0.0, 0.0,
+            r, g, b + saturation, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ];
+        ColorMatrix(matrix)
+    }
+
+    /// Apply the matrix to a non-premultiplied RGBA pixel.
+    pub fn apply(&self, pixel: [u8; 4]) -> [u8; 4] {
+        let m = &self.0;
+        let p = pixel.map(|c| c as f32);
+        let mut out = [0; 4];
+        for (i, row) in m.chunks(5).enumerate() {
+            let value = row[0] * p[0] + row[1] * p[1] + row[2] * p[2] + row[3] * p[3] + row[4];
+            out[i] = value.round().clamp(0.0, 255.0) as u8;
+        }
+        out
+    }
+}
+
+/// The channel order of a bitmap with 8 bits per channel.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum PixelFormat {
+    #[default]
+    Rgba,
+    Bgra,
+}
+
+impl PixelFormat {
+    /// Convert a pixel between this format and RGBA. Swapping is its own
+    /// inverse, so this works in both directions.
+    pub fn swap_rgba(self, pixel: [u8; 4]) -> [u8; 4] {
+        match self {
+            PixelFormat::Rgba => pixel,
+            PixelFormat::Bgra => [pixel[2], pixel[1], pixel[0], pixel[3]],
+        }
+    }
+}
+
+fn premultiply(pixel: [u8; 4]) -> [f32; 4] {
+    let a = pixel[3] as f32 / 255.0;
+    [
+        pixel[0] as f32 / 255.0 * a,
+        pixel[1] as f32 / 255.0 * a,
+        pixel[2] as f32 / 255.0 * a,
+        a,
+    ]
+}
+
+fn unpremultiply(pixel: [f32; 4]) -> [u8; 4] {
+    let a = pixel[3];
+    if a <= 0.0 {
+        return [0; 4];
+    }
+    let channel = |c: f32| ((c / a).clamp(0.0, 1.0) * 255.0).round() as u8;
+    [
+        channel(pixel[0]),
+        channel(pixel[1]),
+        channel(pixel[2]),
+        (a.clamp(0.0, 1.0) * 255.0).round() as u8,
+    ]
+}
@@ -0,0 +1,181 @@
+use crate::{Axis, Insets, NinePatchDrawable, NinePatchError, Section};
+
+impl NinePatchDrawable {
+    /// The drawable mirrored along `axis`, for right-to-left layouts when
+    /// `axis` is [`Axis::Horizontal`].
+    ///
+    /// The sections are reversed and the margins and optical insets on the
+    /// two sides of the axis are swapped. It describes the bitmap returned by
+    /// [`mirror_bitmap`].
+    pub fn mirrored(&self, axis: Axis) -> NinePatchDrawable {
+        let (mut h_sections, mut v_sections) = (self.h_sections.clone(), self.v_sections.clone());
+        let mut margins = Insets {
+            left: self.margin_left,
+            top: self.margin_top,
+            right: self.margin_right,
+            bottom: self.margin_bottom,
+        };
+        let mut optical_insets = self.optical_insets;
+        match axis {
+            Axis::Horizontal => {
+                h_sections = mirror_sections(&self.h_sections, self.width as f32 - 2.0);
+                std::mem::swap(&mut margins.left, &mut margins.right);
+                std::mem::swap(&mut optical_insets.left, &mut optical_insets.right);
+            }
+            Axis::Vertical => {
+                v_sections = mirror_sections(&self.v_sections, self.height as f32 - 2.0);
+                std::mem::swap(&mut margins.top, &mut margins.bottom);
+                std::mem::swap(&mut optical_insets.top, &mut optical_insets.bottom);
+            }
+        }
+        NinePatchDrawable {
+            width: self.width,
+            height: self.height,
+            h_sections,
+            v_sections,
+            margin_left: margins.left,
+            margin_top: margins.top,
+            margin_right: margins.right,
+            margin_bottom: margins.bottom,
+            optical_insets,
+        }
+    }
+}
+
+impl NinePatchDrawable {
+    /// The drawable rotated clockwise by `quarter_turns` times 90 degrees. It
+    /// describes the bitmap returned by [`rotate_bitmap`].
+    ///
+    /// A quarter turn moves the left edge to the top, so the vertical
+    /// sections become the horizontal ones in reverse order, and the margins
+    /// and optical insets rotate with the edges.
+    pub fn rotated(&self, quarter_turns: i32) -> NinePatchDrawable {
+        let mut rotated = self.clone();
+        for _ in 0..quarter_turns.rem_euclid(4) {
+            rotated = NinePatchDrawable {
+                width: rotated.height,
+                height: rotated.width,
+                h_sections: mirror_sections(&rotated.v_sections, rotated.height as f32 - 2.0),
+                v_sections: rotated.h_sections,
+                margin_left: rotated.margin_bottom,
+                margin_top: rotated.margin_left,
+                margin_right: rotated.margin_top,
+                margin_bottom: rotated.margin_right,
+                optical_insets: Insets {
+                    left: rotated.optical_insets.bottom,
+                    top: rotated.optical_insets.left,
+                    right: rotated.optical_insets.top,
+                    bottom: rotated.optical_insets.right,
+                },
+            };
+        }
+        rotated
+    }
+}
+
+fn mirror_sections(sections: &[Section], len: f32) -> Vec<Section> {
+    sections
+        .iter()
+        .rev()
+        .map(|s| Section {
+            start: len - s.start - s.len,
+            len: s.len,
+            kind: s.kind,
+        })
+        .collect()
+}
+
+/// Mirror a nine patch bitmap along `axis`, keeping the marker borders on
+/// their sides so the result is still a valid nine patch. The result has a
+/// stride of `width * 4`.
+pub fn mirror_bitmap(
+    bitmap: &[u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+    axis: Axis,
+) -> Result<Vec<u8>, NinePatchError> {
+    if bitmap.len() != stride * height || stride < width * 4 || width < 3 || height < 3 {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    let mut mirrored = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let sy = match axis {
+            Axis::Vertical if y != 0 && y != height - 1 => height - 1 - y,
+            _ => y,
+        };
+        for x in 0..width {
+            let sx = match axis {
+                Axis::Horizontal if x != 0 && x != width - 1 => width - 1 - x,
+                _ => x,
+            };
+            let o = sy * stride + sx * 4;
+            mirrored.extend_from_slice(&bitmap[o..o + 4]);
+        }
+    }
+    Ok(mirrored)
+}
+
+/// Rotate a nine patch bitmap clockwise by `quarter_turns` times 90 degrees,
+/// keeping the stretch markers on the top and left and the padding markers on
+/// the bottom and right so the result is still a valid nine patch.
+///
+/// The result is `height` x `width` for odd numbers of quarter turns, with a
+/// stride of its width times 4.
+pub fn rotate_bitmap(
+    bitmap: &[u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+    quarter_turns: i32,
+) -> Result<Vec<u8>, NinePatchError> {
+    if bitmap.len() != stride * height || stride < width * 4 || width < 3 || height < 3 {
+        return Err(NinePatchError::InvalidBitmap);
+    }
+    let mut rotated: Vec<u8> = bitmap
+        .chunks(stride)
+        .flat_map(|row| &row[..width * 4])
+        .copied()
+        .collect();
+    let (mut width, mut height) = (width, height);
+    for _ in 0..quarter_turns.rem_euclid(4) {
+        rotated = rotate_once(&rotated, width, height);
+        (width, height) = (height, width);
+    }
+    Ok(rotated)
+}
+
+/// Rotate a bitmap with a stride of `width * 4` by one quarter turn.
+fn rotate_once(bitmap: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let (out_width, out_height) = (height, width);
+    let mut rotated = Vec::with_capacity(bitmap.len());
+    for y in 0..out_height {
+        for x in 0..out_width {
+            // The content and the top and bottom borders turn with the
+            // image. The left and right borders are the old top and bottom
+            // ones, which describe the axis that is now vertical.
+            let (sx, sy) = match x {
+                0 => (y, 0),
+                _ if x == out_width - 1 => (y, height - 1),
+                _ => (y, height - 1 - x),
+            };
+            let o = (sy * width + sx) * 4;
+            rotated.extend_from_slice(&bitmap[o..o + 4]);
+        }
+    }
+    rotated
+}
+
+/// Mirror rendered pixels with a stride of `width * 4` horizontally.
+pub(crate) fn flip_horizontally(pixels: &mut [u8], width: usize) {
+    if width == 0 {
+        return;
+    }
+    for row in pixels.chunks_mut(width * 4) {
+        for x in 0..width / 2 {
+            for c in 0..4 {
+                row.swap(x * 4 + c, (width - 1 - x) * 4 + c);
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::set::check_sections;
+use crate::{Axis, NinePatchDrawable, NinePatchError, NinePatchLayout, Patch, RectF, ScaleOptions};
+
+/// An animation between two layouts, such as a drawable growing from one
+/// size to another or morphing into the drawable of another state.
+///
+/// Each frame interpolates the column and row edges of the two layouts
+/// rather than laying out the drawable again at a whole pixel size in
+/// between, so every edge moves linearly and smoothly, also between
+/// drawables whose fixed sections differ slightly.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NinePatchTransition {
+    from: NinePatchLayout,
+    to: NinePatchLayout,
+}
+
+impl NinePatchTransition {
+    /// Animate `drawable` from a `from` size to a `to` size, both given as
+    /// width and height like for [`NinePatchDrawable::scale_to_with`].
+    pub fn resize(
+        drawable: &NinePatchDrawable,
+        from: (usize, usize),
+        to: (usize, usize),
+        options: &ScaleOptions,
+    ) -> Result<NinePatchTransition, NinePatchError> {
+        Ok(NinePatchTransition {
+            from: NinePatchLayout::new(drawable, from.0, from.1, options)?,
+            to: NinePatchLayout::new(drawable, to.0, to.1, options)?,
+        })
+    }
+
+    /// Animate from `from` at the `from_size` to `to` at the `to_size`.
+    ///
+    /// The drawables must have the same section kinds in the same order and
+    /// fixed sections of about the same lengths, as required by
+    /// [`crate::NinePatchSet`] for drawables of the same density. Draw
+    /// [`NinePatchTransition::patches`] of the first bitmap with an opacity
+    /// of `1 - t` and [`NinePatchTransition::to_patches`] of the second with
+    /// an opacity of `t` to cross-fade between them.
+    pub fn cross_fade(
+        from: &NinePatchDrawable,
+        to: &NinePatchDrawable,
+        from_size: (usize, usize),
+        to_size: (usize, usize),
+        options: &ScaleOptions,
+    ) -> Result<NinePatchTransition, NinePatchError> {
+        check_sections(Axis::Horizontal, &from.h_sections, &to.h_sections, 1.0)?;
+        check_sections(Axis::Vertical, &from.v_sections, &to.v_sections, 1.0)?;
+        Ok(NinePatchTransition {
+            from: NinePatchLayout::new(from, from_size.0, from_size.1, options)?,
+            to: NinePatchLayout::new(to, to_size.0, to_size.1, options)?,
+        })
+    }
+
+    /// The layout at `t = 0.0`.
+    pub fn start(&self) -> &NinePatchLayout {
+        &self.from
+    }
+
+    /// The layout at `t = 1.0`.
+    pub fn end(&self) -> &NinePatchLayout {
+        &self.to
+    }
+
+    /// The target width and height at `t`.
+    pub fn size(&self, t: f32) -> (f32, f32) {
+        (
+            lerp(self.from.width() as f32, self.to.width() as f32, t),
+            lerp(self.from.height() as f32, self.to.height() as f32, t),
+        )
+    }
+
+    /// The patches of the first drawable at `t`, where `0.0` is the first
+    /// layout and `1.0` the second. Values outside of `0.0..=1.0`
+    /// extrapolate, for easing curves that overshoot.
+    pub fn patches(&self, t: f32) -> Vec<Patch> {
+        self.frame(&self.from, t)
+    }
+
+    /// The patches of the second drawable at `t`, with the same targets as
+    /// [`NinePatchTransition::patches`].
+    pub fn to_patches(&self, t: f32) -> Vec<Patch> {
+        self.frame(&self.to, t)
+    }
+
+    fn frame(&self, sources: &NinePatchLayout, t: f32) -> Vec<Patch> {
+        let columns = lerp_edges(self.from.column_edges(), self.to.column_edges(), t);
+        let rows = lerp_edges(self.from.row_edges(), self.to.row_edges(), t);
+        sources
+            .patches()
+            .enumerate()
+            .map(|(i, patch)| {
+                let (column, row) = (i % sources.columns(), i / sources.columns());
+                Patch {
+                    target: RectF {
+                        left: columns[column],
+                        top: rows[row],
+                        right: columns[column + 1],
+                        bottom: rows[row + 1],
+                    },
+                    ..patch
+                }
+            })
+            .collect()
+    }
+}
+
+fn lerp_edges(from: &[f32], to: &[f32], t: f32) -> Vec<f32> {
+    from.iter().zip(to).map(|(&a, &b)| lerp(a, b, t)).collect()
+}
+
+/// Exact at both ends, so the first and last frames match the layouts.
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    (1.0 - t) * a + t * b
+}
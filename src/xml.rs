@@ -0,0 +1,207 @@
+use crate::{NinePatchDrawable, NinePatchError, RenderOptions, Tint, TintMode};
+
+/// An Android `<nine-patch>` drawable resource, as found in
+/// `res/drawable/*.xml`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NinePatchResource {
+    /// The `android:src` reference to the bitmap, such as
+    /// `@drawable/button`.
+    pub src: String,
+    /// `android:dither`. The renderer does not dither, this is only carried
+    /// along for other renderers.
+    pub dither: bool,
+    /// `android:tint` as non-premultiplied RGBA.
+    pub tint: Option<[u8; 4]>,
+    /// `android:tintMode`.
+    pub tint_mode: TintMode,
+    /// `android:autoMirrored`. When set, render with [`RenderOptions::rtl`]
+    /// in right-to-left layouts.
+    pub auto_mirrored: bool,
+}
+
+impl NinePatchResource {
+    /// Parse the XML of a `<nine-patch>` drawable resource.
+    pub fn parse(xml: &str) -> Result<NinePatchResource, NinePatchError> {
+        let element = parse_root_element(xml)?;
+        if element.name != "nine-patch" {
+            return Err(NinePatchError::UnsupportedElement(element.name));
+        }
+        let mut resource = NinePatchResource {
+            src: String::new(),
+            dither: true,
+            tint: None,
+            tint_mode: TintMode::default(),
+            auto_mirrored: false,
+        };
+        let mut src = None;
+        for (name, value) in element.attributes {
+            let syntax = NinePatchError::Syntax { line: element.line };
+            let is_value = matches!(
+                name.as_str(),
+                "android:autoMirrored" | "android:dither" | "android:tint" | "android:tintMode"
+            );
+            if is_value && (value.starts_with('@') || value.starts_with('?')) {
+                return Err(NinePatchError::UnresolvedReference {
+                    attribute: name,
+                    reference: value,
+                });
+            }
+            match name.as_str() {
+                "xmlns" => {}
+                _ if name.starts_with("xmlns:") => {}
+                "android:src" => src = Some(value),
+                "android:autoMirrored" => {
+                    resource.auto_mirrored = value.parse().map_err(|_| syntax)?
+                }
+                "android:dither" => resource.dither = value.parse().map_err(|_| syntax)?,
+                "android:tint" => resource.tint = Some(parse_color(&value).ok_or(syntax)?),
+                "android:tintMode" => {
+                    resource.tint_mode = TintMode::from_android(&value).ok_or(syntax)?
+                }
+                _ => return Err(NinePatchError::UnsupportedAttribute(name)),
+            }
+        }
+        resource.src = src.ok_or(NinePatchError::MissingAttribute("android:src".into()))?;
+        Ok(resource)
+    }
+
+    /// Load the bitmap referenced by `android:src` and parse it.
+    ///
+    /// `load` maps the reference to an RGBA bitmap and its width and height.
+    /// Its border follows the Android convention, see
+    /// [`crate::clear_transparent_border`]. The bitmap is returned along with
+    /// the drawable for rendering.
+    pub fn resolve<F>(&self, load: F) -> Result<(Vec<u8>, NinePatchDrawable), NinePatchError>
+    where
+        F: FnOnce(&str) -> Result<(Vec<u8>, usize, usize), NinePatchError>,
+    {
+        let (mut bitmap, width, height) = load(&self.src)?;
+        crate::clear_transparent_border(&mut bitmap, width * 4, width, height)?;
+        let drawable = NinePatchDrawable::new(&bitmap, width * 4, width, height)?;
+        Ok((bitmap, drawable))
+    }
+
+    /// Render options that apply the tint of the resource.
+    pub fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            tint: self.tint.map(|color| Tint {
+                color,
+                mode: self.tint_mode,
+            }),
+            ..RenderOptions::default()
+        }
+    }
+}
+
+/// Parse an Android color literal: `#RGB`, `#ARGB`, `#RRGGBB` or
+/// `#AARRGGBB`.
+fn parse_color(value: &str) -> Option<[u8; 4]> {
+    let hex = value.strip_prefix('#')?;
+    let digits: Vec<u8> = hex
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    let argb = match digits[..] {
+        [r, g, b] => [0xFF, r * 0x11, g * 0x11, b * 0x11],
+        [a, r, g, b] => [a * 0x11, r * 0x11, g * 0x11, b * 0x11],
+        [r1, r0, g1, g0, b1, b0] => [0xFF, r1 << 4 | r0, g1 << 4 | g0, b1 << 4 | b0],
+        [a1, a0, r1, r0, g1, g0, b1, b0] => {
+            [a1 << 4 | a0, r1 << 4 | r0, g1 << 4 | g0, b1 << 4 | b0]
+        }
+        _ => return None,
+    };
+    Some([argb[1], argb[2], argb[3], argb[0]])
+}
+
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    /// Line of the start tag, numbered from 1.
+    line: usize,
+}
+
+/// Parse the start tag of the root element, skipping the XML declaration,
+/// comments and doctype before it. Children are ignored.
+fn parse_root_element(xml: &str) -> Result<Element, NinePatchError> {
+    let mut rest = xml;
+    let line = |rest: &str| xml[..xml.len() - rest.len()].matches('\n').count() + 1;
+    loop {
+        rest = rest.trim_start();
+        let skip = if rest.starts_with("<?") {
+            "?>"
+        } else if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<!") {
+            ">"
+        } else {
+            break;
+        };
+        let end = rest
+            .find(skip)
+            .ok_or(NinePatchError::Syntax { line: line(rest) })?;
+        rest = &rest[end + skip.len()..];
+    }
+
+    let start_line = line(rest);
+    let syntax = |rest: &str| NinePatchError::Syntax { line: line(rest) };
+    rest = rest.strip_prefix('<').ok_or_else(|| syntax(rest))?;
+    let name_end = rest
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .ok_or_else(|| syntax(rest))?;
+    let name = rest[..name_end].to_string();
+    rest = &rest[name_end..];
+
+    let mut attributes = vec![];
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with("/>") || rest.starts_with('>') {
+            break;
+        }
+        let (attribute, value) = rest.split_once('=').ok_or_else(|| syntax(rest))?;
+        let attribute = attribute.trim();
+        if attribute.is_empty() || attribute.contains(char::is_whitespace) {
+            return Err(syntax(rest));
+        }
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let quote = quote.ok_or_else(|| syntax(value))?;
+        let end = value[1..].find(quote).ok_or_else(|| syntax(value))?;
+        attributes.push((
+            attribute.to_string(),
+            unescape(&value[1..end + 1]).ok_or_else(|| syntax(value))?,
+        ));
+        rest = &value[end + 2..];
+    }
+    Ok(Element {
+        name,
+        attributes,
+        line: start_line,
+    })
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..].find(';')? + start;
+        let c = match &rest[start + 1..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            entity => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Some(unescaped)
+}